@@ -1,7 +1,10 @@
+import { CycleInfo } from "../types/meta";
+
 export * from "../types/meta";
 
 export interface CommBusMessage {
   id: string;
+  function: keyof typeof NavigraphFunction;
   resolve: (value?: unknown) => void;
   reject: (reason: Error) => void;
 }
@@ -9,6 +12,9 @@ export interface CommBusMessage {
 export enum NavigraphEventType {
   Heartbeat = "Heartbeat",
   DownloadProgress = "DownloadProgress",
+  DownloadPhaseChanged = "DownloadPhaseChanged",
+  ExtractionProgress = "ExtractionProgress",
+  NavigationDataChanged = "NavigationDataChanged",
 }
 
 export interface DownloadProgressData {
@@ -16,15 +22,57 @@ export interface DownloadProgressData {
   downloaded_bytes: number;
   current_chunk: number;
   total_chunks: number;
+  /** The average transfer rate since the download was started or resumed, once a chunk has been downloaded */
+  bytes_per_second?: number;
+  /** The estimated amount of seconds until the download finishes */
+  eta_seconds?: number;
+}
+
+export enum DownloadPhase {
+  Downloading = "Downloading",
+  Extracting = "Extracting",
+  Verifying = "Verifying",
+  Activating = "Activating",
+  Done = "Done",
+  Failed = "Failed",
+  Cancelled = "Cancelled",
+}
+
+export interface DownloadPhaseData {
+  phase: DownloadPhase;
+  /** The reason the download failed, only present in the `Failed` phase */
+  error?: string;
+}
+
+export interface ExtractionProgressData {
+  /** The size in bytes of the zip file being extracted */
+  total_bytes: number;
+  /** The amount of bytes of the zip file which have been extracted */
+  extracted_bytes: number;
+}
+
+export interface NavigationDataChangedData {
+  /** The navigation data which was installed before the change, if there was any */
+  old?: CycleInfo;
+  /** The navigation data which is now installed, if it can be read */
+  new?: CycleInfo;
 }
 
 export enum NavigraphFunction {
   DownloadNavigationData = "DownloadNavigationData",
+  InstallNavigationDataFromFile = "InstallNavigationDataFromFile",
+  CancelFunction = "CancelFunction",
   SetDownloadOptions = "SetDownloadOptions",
   GetNavigationDataInstallStatus = "GetNavigationDataInstallStatus",
+  ListInstalledNavigationData = "ListInstalledNavigationData",
+  ActivateNavigationData = "ActivateNavigationData",
+  DeleteNavigationData = "DeleteNavigationData",
   ExecuteSQLQuery = "ExecuteSQLQuery",
   GetDatabaseInfo = "GetDatabaseInfo",
   GetAirport = "GetAirport",
+  FindAirports = "FindAirports",
+  GetAirportByIata = "GetAirportByIata",
+  GetAirportDetails = "GetAirportDetails",
   GetWaypoints = "GetWaypoints",
   GetVhfNavaids = "GetVhfNavaids",
   GetNdbNavaids = "GetNdbNavaids",
@@ -34,14 +82,31 @@ export enum NavigraphFunction {
   GetWaypointsInRange = "GetWaypointsInRange",
   GetVhfNavaidsInRange = "GetVhfNavaidsInRange",
   GetNdbNavaidsInRange = "GetNdbNavaidsInRange",
+  GetNavaidsByFrequency = "GetNavaidsByFrequency",
+  GetReceivableNavaids = "GetReceivableNavaids",
+  GetNavUpdateCandidates = "GetNavUpdateCandidates",
   GetAirwaysInRange = "GetAirwaysInRange",
   GetControlledAirspacesInRange = "GetControlledAirspacesInRange",
   GetRestrictiveAirspacesInRange = "GetRestrictiveAirspacesInRange",
   GetCommunicationsInRange = "GetCommunicationsInRange",
+  GetCommunicationsForFir = "GetCommunicationsForFir",
+  GetCommunicationsByFrequency = "GetCommunicationsByFrequency",
+  GetCenterFrequencyAt = "GetCenterFrequencyAt",
   GetRunwaysAtAirport = "GetRunwaysAtAirport",
+  GetRunwayPairsAtAirport = "GetRunwayPairsAtAirport",
+  GetRunwayComponents = "GetRunwayComponents",
+  GetRunwayAtPosition = "GetRunwayAtPosition",
+  GetGateAtPosition = "GetGateAtPosition",
   GetDeparturesAtAirport = "GetDeparturesAtAirport",
   GetArrivalsAtAirport = "GetArrivalsAtAirport",
   GetApproachesAtAirport = "GetApproachesAtAirport",
+  GetDepartureSummariesAtAirport = "GetDepartureSummariesAtAirport",
+  GetDeparture = "GetDeparture",
+  GetArrivalSummariesAtAirport = "GetArrivalSummariesAtAirport",
+  GetArrival = "GetArrival",
+  GetApproachSummariesAtAirport = "GetApproachSummariesAtAirport",
+  GetApproach = "GetApproach",
+  GetProcedureVerticalProfile = "GetProcedureVerticalProfile",
   GetWaypointsAtAirport = "GetWaypointsAtAirport",
   GetNdbNavaidsAtAirport = "GetNdbNavaidsAtAirport",
   GetGatesAtAirport = "GetGatesAtAirport",
@@ -53,6 +118,17 @@ export enum NavigraphFunction {
 export enum FunctionResultStatus {
   Error = "Error",
   Success = "Success",
+  Cancelled = "Cancelled",
+}
+
+/**
+ * The error which a function call rejects with when it was cancelled before it finished
+ */
+export class FunctionCancelledError extends Error {
+  constructor(message = "Function was cancelled") {
+    super(message);
+    this.name = "FunctionCancelledError";
+  }
 }
 
 export interface FunctionResultArgs {
@@ -1,5 +1,9 @@
 import {
+  AircraftCategory,
   Airport,
+  AirportDetails,
+  AirportDetailsInclude,
+  AirportSearch,
   Airway,
   Approach,
   Arrival,
@@ -7,24 +11,41 @@ import {
   ControlledAirspace,
   Coordinates,
   DatabaseInfo,
+  Degrees,
   Departure,
+  Feet,
   Gate,
   GlsNavaid,
+  Metres,
   NauticalMiles,
+  NavaidWithDistance,
+  NavUpdateCandidates,
   NdbNavaid,
   PathPoint,
+  ProcedureKind,
+  ProcedureSummary,
   RestrictiveAirspace,
+  Runway,
+  RunwaySurface,
   RunwayThreshold,
+  RunwayWindComponents,
+  VerticalProfile,
   VhfNavaid,
   Waypoint,
+  WaypointTypeFilter,
+  Wind,
 } from "../types";
-import { NavigationDataStatus } from "../types/meta";
+import { InstalledNavigationData, NavigationDataStatus } from "../types/meta";
 import {
   Callback,
   CommBusMessage,
+  DownloadPhaseData,
   DownloadProgressData,
+  ExtractionProgressData,
+  FunctionCancelledError,
   FunctionResultArgs,
   FunctionResultStatus,
+  NavigationDataChangedData,
   NavigraphEventType,
   NavigraphFunction,
   RawNavigraphEvent,
@@ -69,11 +90,39 @@ export class NavigraphNavigationDataInterface {
   /**
    * Downloads the navigation data from the given URL to the given path
    *
+   * The navigation data is verified before it replaces the installed navigation data. If it fails verification or
+   * can't be opened, the installed navigation data is kept.
+   *
    * @param url - A valid signed URL to download the navigation data from
+   * @param checksum - The expected CRC32 checksum of the downloaded file, as a hexadecimal string
    * @returns A promise that resolves when the download is complete
    */
-  public async download_navigation_data(url: string): Promise<void> {
-    return await this.callWasmFunction("DownloadNavigationData", { url });
+  public async download_navigation_data(url: string, checksum?: string): Promise<void> {
+    return await this.callWasmFunction("DownloadNavigationData", { url, checksum });
+  }
+
+  /**
+   * Installs navigation data which is already on disk, without any network access
+   *
+   * @param path - The path to either a package (`.zip`, `.s3db.zst` or `.tar.zst`), or a folder containing a `cycle.json` and `.s3db` file, in the package or work folder
+   * @returns A promise that resolves when the navigation data is installed
+   */
+  public async install_navigation_data_from_file(path: string): Promise<void> {
+    return await this.callWasmFunction("InstallNavigationDataFromFile", { path });
+  }
+
+  /**
+   * Cancels the navigation data downloads which are in progress, if any
+   *
+   * @remarks
+   * The pending `download_navigation_data` promises reject with a `FunctionCancelledError`, and any partially downloaded data is removed.
+   * A download which has not started yet is removed from the queue without touching the files of the one running ahead of it.
+   * @returns A promise that resolves once the cancellations have been processed, or rejects if a download finished before its cancellation arrived
+   */
+  public async cancel_download(): Promise<void> {
+    const downloads = this.queue.filter(message => message.function === "DownloadNavigationData");
+
+    await Promise.all(downloads.map(download => this.callWasmFunction("CancelFunction", { id: download.id })));
   }
 
   /**
@@ -95,6 +144,36 @@ export class NavigraphNavigationDataInterface {
     return await this.callWasmFunction("GetNavigationDataInstallStatus", {});
   }
 
+  /**
+   * Lists the navigation data which is installed, both the active navigation data and the inactive navigation data
+   * which is kept after switching to another cycle
+   *
+   * @returns A promise that resolves with the installed navigation data
+   */
+  public async list_installed_navigation_data(): Promise<InstalledNavigationData[]> {
+    return await this.callWasmFunction("ListInstalledNavigationData", {});
+  }
+
+  /**
+   * Activates installed navigation data, keeping the currently active navigation data installed
+   *
+   * @param id - The id of the installed navigation data
+   * @returns A promise that resolves once the navigation data is active
+   */
+  public async activate_navigation_data(id: string): Promise<void> {
+    return await this.callWasmFunction("ActivateNavigationData", { id });
+  }
+
+  /**
+   * Deletes installed navigation data which is not active
+   *
+   * @param id - The id of the installed navigation data
+   * @returns A promise that resolves once the navigation data is deleted
+   */
+  public async delete_navigation_data(id: string): Promise<void> {
+    return await this.callWasmFunction("DeleteNavigationData", { id });
+  }
+
   /**
    * Gets information about the currently active database
    */
@@ -111,6 +190,39 @@ export class NavigraphNavigationDataInterface {
     return await this.callWasmFunction("GetAirport", { ident });
   }
 
+  /**
+   * Searches for airports by their IATA identifier, name, city, country, state or type
+   * @param search - The criteria which the airports must match
+   * @returns A promise that resolves with the list of airports
+   */
+  public async find_airports(search: AirportSearch): Promise<Airport[]> {
+    return await this.callWasmFunction("FindAirports", { search });
+  }
+
+  /**
+   * Gets information about an airport by its IATA identifier
+   * @param iata - The 3 letter IATA identifier of the airport, such as `LHR`
+   * @returns A promise that resolves with the airport
+   */
+  public async get_airport_by_iata(iata: string): Promise<Airport> {
+    return await this.callWasmFunction("GetAirportByIata", { iata });
+  }
+
+  /**
+   * Gets an airport along with the data at it in a single call
+   * @param ident - The identifier of the airport, such as `KJFK`
+   * @param include - The data to include with the airport. Procedures are only included as summaries
+   * @param aircraft_category - If given, only procedures which apply to this aircraft category are included
+   * @returns A promise that resolves with the airport details
+   */
+  public async get_airport_details(
+    ident: string,
+    include: AirportDetailsInclude[],
+    aircraft_category?: AircraftCategory,
+  ): Promise<AirportDetails> {
+    return await this.callWasmFunction("GetAirportDetails", { ident, include, aircraft_category });
+  }
+
   /**
    * Gets a list of waypoints
    * @param ident - The identifier to get the waypoints by
@@ -171,10 +283,15 @@ export class NavigraphNavigationDataInterface {
    * Gets all waypoints within a given range circle around a given point
    * @param center - The center of the range circle
    * @param range - The radius of the range circle (Nautical miles)
+   * @param type_filter - Optional filter on the decoded type and usage of the waypoints
    * @returns A promise that resolves with the list of waypoints
    */
-  public async get_waypoints_in_range(center: Coordinates, range: NauticalMiles): Promise<Waypoint[]> {
-    return await this.callWasmFunction("GetWaypointsInRange", { center, range });
+  public async get_waypoints_in_range(
+    center: Coordinates,
+    range: NauticalMiles,
+    type_filter?: WaypointTypeFilter,
+  ): Promise<Waypoint[]> {
+    return await this.callWasmFunction("GetWaypointsInRange", { center, range, type_filter });
   }
 
   /**
@@ -197,6 +314,46 @@ export class NavigraphNavigationDataInterface {
     return await this.callWasmFunction("GetNdbNavaidsInRange", { center, range });
   }
 
+  /**
+   * Gets all vhf navaids, ndb navaids and localizers which transmit on a frequency, nearest first
+   * @param frequency - The frequency (Megahertz for vhf navaids and localizers, Kilohertz for ndb navaids)
+   * @param near - The position to measure distances from
+   * @param max_results - Optional maximum number of navaids to return
+   * @returns A promise that resolves with the list of navaids
+   */
+  public async get_navaids_by_frequency(
+    frequency: number,
+    near: Coordinates,
+    max_results?: number,
+  ): Promise<NavaidWithDistance[]> {
+    return await this.callWasmFunction("GetNavaidsByFrequency", { frequency, near, max_results });
+  }
+
+  /**
+   * Gets all vhf navaids, ndb navaids and localizers which can be received at a position, nearest first
+   * @param position - The position of the aircraft
+   * @param altitude - The altitude of the aircraft (Feet)
+   * @returns A promise that resolves with the list of navaids
+   */
+  public async get_receivable_navaids(position: Coordinates, altitude: Feet): Promise<NavaidWithDistance[]> {
+    return await this.callWasmFunction("GetReceivableNavaids", { position, altitude });
+  }
+
+  /**
+   * Gets the DME pairs and VOR/DME which give the best radio position update at a position
+   * @param position - The position of the aircraft
+   * @param altitude - The altitude of the aircraft (Feet)
+   * @param count - The maximum number of DME pairs to return
+   * @returns A promise that resolves with the position update candidates
+   */
+  public async get_nav_update_candidates(
+    position: Coordinates,
+    altitude: Feet,
+    count: number,
+  ): Promise<NavUpdateCandidates> {
+    return await this.callWasmFunction("GetNavUpdateCandidates", { position, altitude, count });
+  }
+
   /**
    * Gets all airways which have a fix which falls within a given range circle around a given point
    * @param center - The center of the range circle
@@ -243,6 +400,41 @@ export class NavigraphNavigationDataInterface {
     return await this.callWasmFunction("GetCommunicationsInRange", { center, range });
   }
 
+  /**
+   * Gets all enroute communications of an FIR or UIR, along with the communications of every airport within it
+   * @param fir_ident - The identifier of the FIR or UIR
+   * @returns A promise that resolves with the list of communications
+   */
+  public async get_communications_for_fir(fir_ident: string): Promise<Communication[]> {
+    return await this.callWasmFunction("GetCommunicationsForFir", { fir_ident });
+  }
+
+  /**
+   * Gets all airport and enroute communications which transmit on a frequency, nearest first
+   * @param frequency - The frequency of the communications
+   * @param near - The position to measure distances from
+   * @param range - Optional radius around `near` to limit the search to (Nautical miles)
+   * @param max_results - Optional maximum number of communications to return
+   * @returns A promise that resolves with the list of communications
+   */
+  public async get_communications_by_frequency(
+    frequency: number,
+    near: Coordinates,
+    range?: NauticalMiles,
+    max_results?: number,
+  ): Promise<Communication[]> {
+    return await this.callWasmFunction("GetCommunicationsByFrequency", { frequency, near, range, max_results });
+  }
+
+  /**
+   * Gets the center frequency responsible for a position, using the FIR or UIR which contains the position
+   * @param position - The position of the aircraft
+   * @returns A promise that resolves with the communication, or null if no FIR or UIR contains the position
+   */
+  public async get_center_frequency_at(position: Coordinates): Promise<Communication | null> {
+    return await this.callWasmFunction("GetCenterFrequencyAt", { position });
+  }
+
   /**
    * Gets all runways which serve an airport
    * @param airport_ident - The 4 letter identifier of the airport
@@ -252,31 +444,201 @@ export class NavigraphNavigationDataInterface {
     return await this.callWasmFunction("GetRunwaysAtAirport", { airport_ident });
   }
 
+  /**
+   * Gets all runways which serve an airport, with reciprocal thresholds paired into a single runway
+   * @param airport_ident - The 4 letter identifier of the airport
+   * @returns A promise that resolves with the list of runways
+   */
+  public async get_runway_pairs_at_airport(airport_ident: string): Promise<Runway[]> {
+    return await this.callWasmFunction("GetRunwayPairsAtAirport", { airport_ident });
+  }
+
+  /**
+   * Gets the wind components of all runways which serve an airport, ranked from most to least suitable
+   * @param airport_ident - The 4 letter identifier of the airport
+   * @param wind - The wind to resolve into components
+   * @param min_length - Optional minimum runway length (Feet)
+   * @param surfaces - Optional list of runway surfaces to include
+   * @returns A promise that resolves with the list of runway wind components
+   */
+  public async get_runway_components(
+    airport_ident: string,
+    wind: Wind,
+    min_length?: Feet,
+    surfaces?: RunwaySurface[],
+  ): Promise<RunwayWindComponents[]> {
+    return await this.callWasmFunction("GetRunwayComponents", { airport_ident, wind, min_length, surfaces });
+  }
+
+  /**
+   * Gets the runway which an aircraft is lined up on
+   * @param position - The position of the aircraft
+   * @param true_heading - The true heading of the aircraft (Degrees)
+   * @returns A promise that resolves with the runway, or null if the aircraft is not lined up on a runway
+   */
+  public async get_runway_at_position(position: Coordinates, true_heading: Degrees): Promise<RunwayThreshold | null> {
+    return await this.callWasmFunction("GetRunwayAtPosition", { position, true_heading });
+  }
+
+  /**
+   * Gets the gate nearest to a position
+   * @param position - The position to search around
+   * @param tolerance - The maximum distance to the gate (Metres)
+   * @returns A promise that resolves with the gate, or null if there is no gate within the tolerance
+   */
+  public async get_gate_at_position(position: Coordinates, tolerance: Metres): Promise<Gate | null> {
+    return await this.callWasmFunction("GetGateAtPosition", { position, tolerance });
+  }
+
   /**
    * Gets all departure procedures which serve an airport
    * @param airport_ident - The 4 letter identifier of the airport
+   * @param aircraft_category - If given, only procedures and legs which apply to this aircraft category are returned
    * @returns A promise that resolves with the list of departures
    */
-  public async get_departures_at_airport(airport_ident: string): Promise<Departure[]> {
-    return await this.callWasmFunction("GetDeparturesAtAirport", { airport_ident });
+  public async get_departures_at_airport(
+    airport_ident: string,
+    aircraft_category?: AircraftCategory,
+  ): Promise<Departure[]> {
+    return await this.callWasmFunction("GetDeparturesAtAirport", { airport_ident, aircraft_category });
   }
 
   /**
    * Gets all arrival procedures which serve an airport
    * @param airport_ident - The 4 letter identifier of the airport
+   * @param aircraft_category - If given, only procedures and legs which apply to this aircraft category are returned
    * @returns A promise that resolves with the list of arrivals
    */
-  public async get_arrivals_at_airport(airport_ident: string): Promise<Arrival[]> {
-    return await this.callWasmFunction("GetArrivalsAtAirport", { airport_ident });
+  public async get_arrivals_at_airport(
+    airport_ident: string,
+    aircraft_category?: AircraftCategory,
+  ): Promise<Arrival[]> {
+    return await this.callWasmFunction("GetArrivalsAtAirport", { airport_ident, aircraft_category });
   }
 
   /**
    * Gets all approach procedures which serve an airport
    * @param airport_ident - The 4 letter identifier of the airport
+   * @param aircraft_category - If given, only procedures and legs which apply to this aircraft category are returned
    * @returns A promise that resolves with the list of approaches
    */
-  public async get_approaches_at_airport(airport_ident: string): Promise<Approach[]> {
-    return await this.callWasmFunction("GetApproachesAtAirport", { airport_ident });
+  public async get_approaches_at_airport(
+    airport_ident: string,
+    aircraft_category?: AircraftCategory,
+  ): Promise<Approach[]> {
+    return await this.callWasmFunction("GetApproachesAtAirport", { airport_ident, aircraft_category });
+  }
+
+  /**
+   * Gets summaries of all departure procedures which serve an airport, without their legs
+   * @param airport_ident - The 4 letter identifier of the airport
+   * @param aircraft_category - If given, only procedures and legs which apply to this aircraft category are returned
+   * @returns A promise that resolves with the list of departure summaries
+   */
+  public async get_departure_summaries_at_airport(
+    airport_ident: string,
+    aircraft_category?: AircraftCategory,
+  ): Promise<ProcedureSummary[]> {
+    return await this.callWasmFunction("GetDepartureSummariesAtAirport", { airport_ident, aircraft_category });
+  }
+
+  /**
+   * Gets a departure procedure at an airport
+   * @param airport_ident - The 4 letter identifier of the airport
+   * @param ident - The identifier of the departure
+   * @param aircraft_category - If given, only procedures and legs which apply to this aircraft category are returned
+   * @returns A promise that resolves with the departure
+   */
+  public async get_departure(
+    airport_ident: string,
+    ident: string,
+    aircraft_category?: AircraftCategory,
+  ): Promise<Departure> {
+    return await this.callWasmFunction("GetDeparture", { airport_ident, ident, aircraft_category });
+  }
+
+  /**
+   * Gets summaries of all arrival procedures which serve an airport, without their legs
+   * @param airport_ident - The 4 letter identifier of the airport
+   * @param aircraft_category - If given, only procedures and legs which apply to this aircraft category are returned
+   * @returns A promise that resolves with the list of arrival summaries
+   */
+  public async get_arrival_summaries_at_airport(
+    airport_ident: string,
+    aircraft_category?: AircraftCategory,
+  ): Promise<ProcedureSummary[]> {
+    return await this.callWasmFunction("GetArrivalSummariesAtAirport", { airport_ident, aircraft_category });
+  }
+
+  /**
+   * Gets an arrival procedure at an airport
+   * @param airport_ident - The 4 letter identifier of the airport
+   * @param ident - The identifier of the arrival
+   * @param aircraft_category - If given, only procedures and legs which apply to this aircraft category are returned
+   * @returns A promise that resolves with the arrival
+   */
+  public async get_arrival(
+    airport_ident: string,
+    ident: string,
+    aircraft_category?: AircraftCategory,
+  ): Promise<Arrival> {
+    return await this.callWasmFunction("GetArrival", { airport_ident, ident, aircraft_category });
+  }
+
+  /**
+   * Gets summaries of all approach procedures which serve an airport, without their legs
+   * @param airport_ident - The 4 letter identifier of the airport
+   * @param aircraft_category - If given, only procedures and legs which apply to this aircraft category are returned
+   * @returns A promise that resolves with the list of approach summaries
+   */
+  public async get_approach_summaries_at_airport(
+    airport_ident: string,
+    aircraft_category?: AircraftCategory,
+  ): Promise<ProcedureSummary[]> {
+    return await this.callWasmFunction("GetApproachSummariesAtAirport", { airport_ident, aircraft_category });
+  }
+
+  /**
+   * Gets an approach procedure at an airport
+   * @param airport_ident - The 4 letter identifier of the airport
+   * @param ident - The identifier of the approach
+   * @param aircraft_category - If given, only procedures and legs which apply to this aircraft category are returned
+   * @returns A promise that resolves with the approach
+   */
+  public async get_approach(
+    airport_ident: string,
+    ident: string,
+    aircraft_category?: AircraftCategory,
+  ): Promise<Approach> {
+    return await this.callWasmFunction("GetApproach", { airport_ident, ident, aircraft_category });
+  }
+
+  /**
+   * Gets the altitude and speed constraints along a procedure, and checks that they can be met
+   * @param airport_ident - The 4 letter identifier of the airport
+   * @param kind - The kind of procedure
+   * @param ident - The identifier of the procedure
+   * @param runway_transition - The runway transition to include, ignored for approaches
+   * @param enroute_transition - The enroute transition to include, or the approach transition for approaches
+   * @param aircraft_category - If given, only legs which apply to this aircraft category are included
+   * @returns A promise that resolves with the vertical profile
+   */
+  public async get_procedure_vertical_profile(
+    airport_ident: string,
+    kind: ProcedureKind,
+    ident: string,
+    runway_transition?: string,
+    enroute_transition?: string,
+    aircraft_category?: AircraftCategory,
+  ): Promise<VerticalProfile> {
+    return await this.callWasmFunction("GetProcedureVerticalProfile", {
+      airport_ident,
+      kind,
+      ident,
+      runway_transition,
+      enroute_transition,
+      aircraft_category,
+    });
   }
 
   /**
@@ -309,10 +671,18 @@ export class NavigraphNavigationDataInterface {
   /**
    * Gets all communications which are at an airport
    * @param airport_ident - The 4 letter identifier of the airport
+   * @param active_at - Optional time (seconds since the unix epoch, UTC) at which the communications must be operating
+   * @param position - Optional position which must be within the sector of the communications
+   * @param altitude - Optional altitude which must be within the sector of the communications (Feet)
    * @returns A promise that resolves with the list of communications
    */
-  public async get_communications_at_airport(airport_ident: string): Promise<Communication[]> {
-    return await this.callWasmFunction("GetCommunicationsAtAirport", { airport_ident });
+  public async get_communications_at_airport(
+    airport_ident: string,
+    active_at?: number,
+    position?: Coordinates,
+    altitude?: Feet,
+  ): Promise<Communication[]> {
+    return await this.callWasmFunction("GetCommunicationsAtAirport", { airport_ident, active_at, position, altitude });
   }
 
   /**
@@ -354,6 +724,7 @@ export class NavigraphNavigationDataInterface {
     return new Promise((resolve, reject) => {
       this.queue.push({
         id,
+        function: name,
         resolve: (response: unknown) => resolve(response as T),
         reject: (error: Error) => reject(error),
       });
@@ -375,6 +746,8 @@ export class NavigraphNavigationDataInterface {
         const data = args.data;
         if (args.status === FunctionResultStatus.Success) {
           message.resolve(data);
+        } else if (args.status === FunctionResultStatus.Cancelled) {
+          message.reject(new FunctionCancelledError(typeof data === "string" ? data : undefined));
         } else {
           message.reject(new Error(typeof data === "string" ? data : "Unknown error"));
         }
@@ -402,6 +775,12 @@ export class NavigraphNavigationDataInterface {
 
   public onEvent(event: NavigraphEventType.Heartbeat, callback: () => void): void;
   public onEvent(event: NavigraphEventType.DownloadProgress, callback: (data: DownloadProgressData) => void): void;
+  public onEvent(event: NavigraphEventType.DownloadPhaseChanged, callback: (data: DownloadPhaseData) => void): void;
+  public onEvent(event: NavigraphEventType.ExtractionProgress, callback: (data: ExtractionProgressData) => void): void;
+  public onEvent(
+    event: NavigraphEventType.NavigationDataChanged,
+    callback: (data: NavigationDataChangedData) => void,
+  ): void;
 
   /**
    * Registers a callback to be called when an event is received
@@ -1,4 +1,10 @@
+import { Communication } from "./communication";
+import { Gate } from "./gate";
+import { GlsNavaid } from "./gls_navaid";
 import { Coordinates, Degrees, Feet, Knots } from "./math";
+import { PathPoint } from "./path_point";
+import { ProcedureSummary } from "./procedure";
+import { RunwayThreshold } from "./runway_threshold";
 
 export enum IfrCapability {
   Yes = "Y",
@@ -35,3 +41,47 @@ export interface Airport {
   iata_ident?: string;
   magnetic_variation?: Degrees;
 }
+
+/**
+ * A search for airports. Every criteria which is set must match, text criteria are case insensitive
+ */
+export interface AirportSearch {
+  /** The IATA identifier of the airport, also matches the 3 letter identifier of the airport */
+  iata?: string;
+  /** Text which the formal name of the airport must contain */
+  name_contains?: string;
+  city?: string;
+  /** The name or 3 letter code of the country of the airport */
+  country?: string;
+  /** The name or 2 letter code of the state of the airport */
+  state?: string;
+  type?: string;
+  /** The maximum number of airports to return */
+  limit: number;
+}
+
+export enum AirportDetailsInclude {
+  Runways = "runways",
+  Communications = "communications",
+  Gates = "gates",
+  GlsNavaids = "gls_navaids",
+  PathPoints = "path_points",
+  Departures = "departures",
+  Arrivals = "arrivals",
+  Approaches = "approaches",
+}
+
+/**
+ * An airport along with the data at it. Each list is only present if it was requested
+ */
+export interface AirportDetails {
+  airport: Airport;
+  runways?: RunwayThreshold[];
+  communications?: Communication[];
+  gates?: Gate[];
+  gls_navaids?: GlsNavaid[];
+  path_points?: PathPoint[];
+  departures?: ProcedureSummary[];
+  arrivals?: ProcedureSummary[];
+  approaches?: ProcedureSummary[];
+}
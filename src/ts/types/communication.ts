@@ -1,4 +1,36 @@
-import { Coordinates } from "./math";
+import { Coordinates, Degrees, Feet, Minutes } from "./math";
+
+export enum Weekday {
+  Monday = "MON",
+  Tuesday = "TUE",
+  Wednesday = "WED",
+  Thursday = "THU",
+  Friday = "FRI",
+  Saturday = "SAT",
+  Sunday = "SUN",
+}
+
+export interface OperatingPeriod {
+  /** The days on which this period starts */
+  days: Weekday[];
+  /** The start of this period in minutes after midnight UTC */
+  start: Minutes;
+  /** The end of this period in minutes after midnight UTC, if before `start` the period ends on the following day */
+  end: Minutes;
+}
+
+export interface OperatingSchedule {
+  periods: OperatingPeriod[];
+}
+
+export interface CommunicationSector {
+  /** The magnetic bearing from the sector facility at which this sector starts, going clockwise */
+  bearing_from: Degrees;
+  /** The magnetic bearing from the sector facility at which this sector ends */
+  bearing_to: Degrees;
+  lower_altitude?: Feet;
+  upper_altitude?: Feet;
+}
 
 export enum FrequencyUnits {
   High = "H",
@@ -70,4 +102,9 @@ export interface Communication {
   sector_facility?: string;
   sector_facility_icao_code?: string;
   sectorization?: string;
+  sector?: CommunicationSector;
+  schedule?: OperatingSchedule;
+  guard_transmit?: string;
+  narrative?: string;
+  service_indicator?: string;
 }
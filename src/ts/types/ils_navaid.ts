@@ -0,0 +1,28 @@
+import { Coordinates, Degrees, Feet, MegaHertz } from "./math";
+
+export interface IlsNavaid {
+  /** The Geographic region where this navaid is */
+  area_code?: string;
+  /** The identifier of the airport which this navaid serves */
+  airport_ident: string;
+  /** The icao prefix of the region this navaid is in */
+  icao_code: string;
+  /** The identifier of this navaid, such as `IKEN` or `ILL` */
+  ident: string;
+  /** The identifier of the runway this navaid serves */
+  runway_ident?: string;
+  frequency: MegaHertz;
+  /** The location of the localizer antenna */
+  location: Coordinates;
+  /** The magnetic bearing of the localizer course */
+  bearing?: Degrees;
+  /** The width of the localizer course */
+  width?: Degrees;
+  /** The category of this navaid, such as `1` or `3` */
+  category?: string;
+  /** The location of the glideslope antenna, if this navaid has a glideslope */
+  glideslope_location?: Coordinates;
+  glideslope_angle?: Degrees;
+  glideslope_elevation?: Feet;
+  station_declination?: Degrees;
+}
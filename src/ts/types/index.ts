@@ -6,11 +6,13 @@ export * from "./database_info";
 export * from "./fix";
 export * from "./gate";
 export * from "./gls_navaid";
+export * from "./ils_navaid";
 export * from "./math";
 export * from "./ndb_navaid";
 export * from "./path_point";
 export * from "./procedure";
 export * from "./ProcedureLeg";
+export * from "./radio_navaid";
 export * from "./runway_threshold";
 export * from "./vhfnavaid";
 export * from "./waypoint";
@@ -13,3 +13,24 @@ export interface NavigationDataStatus {
   validityPeriod: string | null;
   latestCycle: string | null;
 }
+
+export enum NavigationDataSource {
+  Bundled = "Bundled",
+  Downloaded = "Downloaded",
+  Sideloaded = "Sideloaded",
+}
+
+export interface CycleInfo {
+  cycle: string;
+  revision: string;
+  format: string;
+  validityPeriod: string;
+  source: NavigationDataSource;
+}
+
+export interface InstalledNavigationData extends CycleInfo {
+  /** The id to pass to `activate_navigation_data` or `delete_navigation_data` */
+  id: string;
+  /** Whether this is the navigation data which queries are served from */
+  active: boolean;
+}
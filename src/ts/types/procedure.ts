@@ -1,4 +1,18 @@
-import { ProcedureLeg } from "./ProcedureLeg";
+import { Fix } from "./fix";
+import { Degrees, Feet, NauticalMiles } from "./math";
+import { LegType, ProcedureLeg, SpeedConstraint } from "./ProcedureLeg";
+
+/**
+ * The approach category of an aircraft, based on its speed at the threshold
+ */
+export enum AircraftCategory {
+  A = "A",
+  B = "B",
+  C = "C",
+  D = "D",
+  E = "E",
+  Helicopter = "H",
+}
 
 export interface Transition {
   ident: string;
@@ -54,4 +68,76 @@ export interface Approach {
 
   runway_ident: string;
   approach_type: ApproachType;
+  capabilities: ApproachCapabilities;
+}
+
+/**
+ * The navigation capabilities which are required or available to fly an approach
+ */
+export interface ApproachCapabilities {
+  /** The smallest RNP of any leg in the approach, excluding the missed approach */
+  rnp?: NauticalMiles;
+  /** Whether any leg of the approach requires authorization (RNP AR) */
+  authorization_required: boolean;
+  lnav: boolean;
+  lnav_vnav: boolean;
+  lpv: boolean;
+  lp: boolean;
+  /** The ILS or GLS navaid which provides guidance for the final approach */
+  navaid?: Fix;
+  final_approach_fix?: Fix;
+  missed_approach_point?: Fix;
+}
+
+export interface ProcedureSummary {
+  ident: string;
+  /** The idents of the runways which this procedure serves, such as `RW27L` */
+  runway_transitions: string[];
+  /** The idents of the enroute transitions of this procedure. For approaches, these are the approach transitions */
+  enroute_transitions: string[];
+  /** Only present for approaches */
+  approach_type?: ApproachType;
+  /** The smallest RNP of any leg in this procedure, excluding the missed approach */
+  rnp?: NauticalMiles;
+  /** Whether any leg of this procedure (excluding the missed approach) requires authorization (RNP AR) */
+  authorization_required: boolean;
+}
+
+export enum ProcedureKind {
+  Departure = "Departure",
+  Arrival = "Arrival",
+  Approach = "Approach",
+}
+
+/**
+ * A point along a procedure at the termination of one of its legs
+ */
+export interface VerticalProfilePoint {
+  /** The ident of the fix which the leg terminates at */
+  ident?: string;
+  leg_type: LegType;
+  /** The along-track distance from the start of the procedure, only present for legs which terminate at a fix */
+  distance?: NauticalMiles;
+  min_altitude?: Feet;
+  max_altitude?: Feet;
+  speed?: SpeedConstraint;
+  vertical_angle?: Degrees;
+}
+
+/**
+ * A pair of altitude constraints which can not be met without a gradient steeper than 6 degrees
+ */
+export interface VerticalProfileIssue {
+  /** The index of the first point in `points` */
+  from: number;
+  /** The index of the second point in `points` */
+  to: number;
+  /** The smallest climb (positive) or descent (negative) gradient which meets both constraints */
+  required_gradient: Degrees;
+}
+
+export interface VerticalProfile {
+  points: VerticalProfilePoint[];
+  total_distance: NauticalMiles;
+  issues: VerticalProfileIssue[];
 }
@@ -0,0 +1,31 @@
+import { IlsNavaid } from "./ils_navaid";
+import { Degrees, NauticalMiles } from "./math";
+import { NdbNavaid } from "./ndb_navaid";
+import { VhfNavaid } from "./vhfnavaid";
+
+export type RadioNavaid =
+  | ({ type: "Vhf" } & VhfNavaid)
+  | ({ type: "Ndb" } & NdbNavaid)
+  | ({ type: "Ils" } & IlsNavaid);
+
+export interface NavaidWithDistance {
+  navaid: RadioNavaid;
+  /** The distance from the reference position to the navaid */
+  distance: NauticalMiles;
+}
+
+export interface DmeDmePair {
+  first: NavaidWithDistance;
+  second: NavaidWithDistance;
+  /** The angle between the lines of position of both DMEs, between 30 and 90 degrees */
+  crossing_angle: Degrees;
+  /** The estimated error of a position fixed using this pair */
+  estimated_error: NauticalMiles;
+}
+
+export interface NavUpdateCandidates {
+  /** The best DME pairs to update the position with, most accurate first */
+  dme_dme: DmeDmePair[];
+  /** The best collocated VOR/DME to update the position with */
+  vor_dme?: NavaidWithDistance;
+}
@@ -1,4 +1,5 @@
-import { Coordinates, Degrees, Feet } from "./math";
+import { TurnDirection } from "./ProcedureLeg";
+import { Coordinates, Degrees, Feet, Knots } from "./math";
 
 // Im not sure why we chose an enum, but I think its because its Y/N in the DFDv2 Spec
 export enum RunwayLights {
@@ -54,3 +55,65 @@ export interface RunwayThreshold {
   surface?: RunwaySurface;
   traffic_pattern?: TrafficPattern;
 }
+
+export interface RunwayEnd {
+  ident: string;
+  true_bearing: Degrees;
+  magnetic_bearing: Degrees;
+  gradient: Degrees;
+  /** The physical start of the runway at this end, before any displaced threshold */
+  start: Coordinates;
+  /** The physical end of the runway when travelling from this end */
+  end: Coordinates;
+  landing_threshold: Coordinates;
+  elevation: Feet;
+  displaced_threshold_distance?: Feet;
+  threshold_crossing_height?: Feet;
+  ils_ident?: string;
+  ils_category?: string;
+  traffic_pattern?: TrafficPattern;
+}
+
+export interface Runway {
+  /** The identifier made up of both runway ends, such as `09L/27R` */
+  ident: string;
+  icao_code: string;
+  length: Feet;
+  width: Feet;
+  lights?: RunwayLights;
+  surface?: RunwaySurface;
+  /** The lower numbered end of the runway */
+  first: RunwayEnd;
+  /** The higher numbered end of the runway, if it is present in the database */
+  second?: RunwayEnd;
+  /** The four corners of the runway surface */
+  polygon: Coordinates[];
+}
+
+export enum WindReference {
+  /** Wind direction relative to true north, as reported in a METAR */
+  True = "True",
+  /** Wind direction relative to magnetic north, as broadcast on the ATIS */
+  Magnetic = "Magnetic",
+}
+
+export interface Wind {
+  /** The direction the wind is blowing from */
+  direction: Degrees;
+  speed: Knots;
+  gust?: Knots;
+  /** Defaults to `WindReference.True` */
+  reference?: WindReference;
+}
+
+export interface RunwayWindComponents {
+  runway: RunwayThreshold;
+  headwind: Knots;
+  tailwind: Knots;
+  crosswind: Knots;
+  /** The side of the runway which the crosswind is blowing from */
+  crosswind_direction: TurnDirection;
+  gust_headwind?: Knots;
+  gust_tailwind?: Knots;
+  gust_crosswind?: Knots;
+}
@@ -1,4 +1,34 @@
-import { Coordinates, Degrees, MegaHertz, NauticalMiles } from "./math";
+import { Coordinates, Degrees, Feet, MegaHertz, NauticalMiles } from "./math";
+
+export enum VhfNavaidType {
+  Vor = "Vor",
+  VorDme = "VorDme",
+  Vortac = "Vortac",
+  Tacan = "Tacan",
+  Dme = "Dme",
+  IlsDme = "IlsDme",
+  IlsTacan = "IlsTacan",
+  MlsDme = "MlsDme",
+  Unknown = "Unknown",
+}
+
+export enum VhfNavaidCoverage {
+  Terminal = "T",
+  LowAltitude = "L",
+  HighAltitude = "H",
+  Undefined = "U",
+  /** Coverage class used for ILS/TACAN installations */
+  IlsTacan = "C",
+}
+
+export interface VhfNavaidClass {
+  navaid_type: VhfNavaidType;
+  coverage?: VhfNavaidCoverage;
+  voice: boolean;
+  weather_broadcast: boolean;
+  biased: boolean;
+  collocated: boolean;
+}
 
 export interface VhfNavaid {
   area_code: string;
@@ -14,4 +44,10 @@ export interface VhfNavaid {
   magnetic_variation?: Degrees;
   station_declination?: Degrees;
   range?: NauticalMiles;
+  class: VhfNavaidClass;
+  dme_ident?: string;
+  dme_location?: Coordinates;
+  dme_elevation?: Feet;
+  airport_elevation?: Feet;
+  ilsdme_bias?: NauticalMiles;
 }
@@ -1,5 +1,52 @@
 import { Coordinates, Degrees } from "./math";
 
+export enum WaypointUsage {
+  High = "H",
+  Low = "L",
+  Both = "B",
+  /** Waypoint used only within terminal procedures */
+  Terminal = "T",
+}
+
+export interface WaypointType {
+  rnav: boolean;
+  named_intersection: boolean;
+  unnamed_charted: boolean;
+  uncharted_airway_intersection: boolean;
+  vfr_reporting_point: boolean;
+  ndb: boolean;
+  arc_center: boolean;
+  initial_approach_fix: boolean;
+  intermediate_approach_fix: boolean;
+  final_approach_fix: boolean;
+  final_approach_course_fix: boolean;
+  missed_approach_fix: boolean;
+  off_route: boolean;
+  oceanic_entry_exit: boolean;
+  fir_uir_boundary: boolean;
+  lat_long_intersection: boolean;
+  sid: boolean;
+  star: boolean;
+  approach: boolean;
+}
+
+/**
+ * A filter on the decoded type of waypoints. Each flag which is set must match the waypoint, unset flags are ignored.
+ */
+export interface WaypointTypeFilter {
+  rnav?: boolean;
+  named_intersection?: boolean;
+  unnamed_charted?: boolean;
+  vfr_reporting_point?: boolean;
+  initial_approach_fix?: boolean;
+  final_approach_fix?: boolean;
+  fir_uir_boundary?: boolean;
+  /** Whether the waypoint is used in any SID, STAR or approach */
+  terminal_procedure?: boolean;
+  /** If set, only waypoints with one of these usages are included */
+  usage?: WaypointUsage[];
+}
+
 export interface Waypoint {
   area_code: string;
   airport_ident?: string;
@@ -11,4 +58,6 @@ export interface Waypoint {
   name: string;
   location: Coordinates;
   magnetic_variation?: Degrees;
+  waypoint_type: WaypointType;
+  usage: WaypointUsage;
 }
@@ -2,22 +2,27 @@ mod types;
 mod utils;
 
 use anyhow::{anyhow, Result};
-use once_cell::sync::Lazy;
+use once_cell::{sync::Lazy, unsync::OnceCell};
 use sentry::integrations::anyhow::capture_anyhow;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::{
     cmp::Ordering,
+    collections::HashMap,
     fs::{self, read_dir, File},
+    io::ErrorKind,
     path::{Path, PathBuf},
     sync::Mutex,
 };
 
-use rusqlite::{params, params_from_iter, types::ValueRef, Connection, OpenFlags};
+use rusqlite::{params, params_from_iter, types::ValueRef, Connection, OpenFlags, ToSql};
 use serde_json::{Number, Value};
-pub use utils::{Coordinates, NauticalMiles};
+
+use crate::{InterfaceEvent, NavigationDataChangedEvent};
+use types::database_info::parse_from_to;
+pub use utils::{Coordinates, Degrees, Feet, Meters, NauticalMiles};
 
 pub use types::{
-    airport::Airport,
+    airport::{Airport, AirportDetails, AirportSearch},
     airspace::{
         map_controlled_airspaces, map_restrictive_airspaces, ControlledAirspace,
         RestrictiveAirspace,
@@ -25,19 +30,28 @@ pub use types::{
     airway::{map_airways, Airway},
     communication::Communication,
     database_info::DatabaseInfo,
+    enums::{
+        AircraftCategory, AirportDetailsInclude, CommunicationType, ProcedureKind, RunwaySurface,
+        WindReference,
+    },
+    fir_uir::{map_fir_uir_boundaries, FirUirBounds},
     gate::Gate,
     gls_navaid::GlsNavaid,
     ndb_navaid::NdbNavaid,
     path_point::PathPoint,
     procedure::{
-        approach::{map_approaches, Approach},
-        arrival::{map_arrivals, Arrival},
-        departure::{map_departures, Departure},
+        applies_to_aircraft_category,
+        approach::{map_approach_summaries, map_approaches, Approach},
+        arrival::{map_arrival_summaries, map_arrivals, Arrival},
+        departure::{map_departure_summaries, map_departures, Departure},
+        filter_aircraft_category, ProcedureSummary,
     },
-    runway::RunwayThreshold,
+    radio_navaid::{NavUpdateCandidates, NavaidWithDistance, RadioNavaid, MAX_RECEPTION_RANGE},
+    runway::{map_runways, Runway, RunwayThreshold, RunwayWindComponents, Wind},
     sql,
+    vertical_profile::VerticalProfile,
     vhf_navaid::VhfNavaid,
-    waypoint::Waypoint,
+    waypoint::{Waypoint, WaypointTypeFilter},
 };
 
 /// The path to the navigation data files folder in the work directory
@@ -46,9 +60,50 @@ pub const WORK_NAVIGATION_DATA_FOLDER: &str = "\\work/NavigationData";
 pub const WORK_CYCLE_JSON_PATH: &str = "\\work/NavigationData/cycle.json";
 /// The path to the "master" SQLite DB
 pub const WORK_DB_PATH: &str = "\\work/NavigationData/db.s3db";
+/// The path to the folder which new navigation data is extracted into, before it is verified and installed
+pub const WORK_STAGING_FOLDER: &str = "\\work/NavigationDataStaging";
+/// The path to the cycle info JSON in the staging folder
+pub const WORK_STAGING_CYCLE_JSON_PATH: &str = "\\work/NavigationDataStaging/cycle.json";
+/// The path to the SQLite DB in the staging folder
+pub const WORK_STAGING_DB_PATH: &str = "\\work/NavigationDataStaging/db.s3db";
+/// The path to the folder which the installed navigation data is moved to while new navigation data is swapped in
+const WORK_BACKUP_FOLDER: &str = "\\work/NavigationDataBackup";
+/// The path to the cycle info JSON in the backup folder
+const WORK_BACKUP_CYCLE_JSON_PATH: &str = "\\work/NavigationDataBackup/cycle.json";
+/// The path to the folder which holds the installed navigation data which isn't active, with one folder per id
+const WORK_LIBRARY_FOLDER: &str = "\\work/NavigationDataLibrary";
+/// The path to the list of ids of the bundled navigation data which has been activated before
+const WORK_ACTIVATED_BUNDLED_IDS_PATH: &str = "\\work/ng_activated_bundled.json";
+/// The format of the navigation data which this interface reads, used for cycle info JSONs created from a database
+const DATABASE_FORMAT: &str = "dfdv2";
 /// The folder name for bundled navigation data
 pub const BUNDLED_FOLDER_NAME: &str = ".\\Navigraph/BundledData";
 
+/// Selects the VHF navaids along with the elevation of their associated airport, as the elevation of the station
+const VHF_NAVAIDS_WITH_ELEVATION_QUERY: &str =
+    "SELECT tbl_d_vhfnavaids.*, tbl_pa_airports.elevation AS airport_elevation \
+    FROM tbl_d_vhfnavaids LEFT JOIN tbl_pa_airports \
+    ON tbl_pa_airports.airport_identifier = tbl_d_vhfnavaids.airport_identifier";
+
+/// The tables which must be present in a database for it to be installed
+const REQUIRED_TABLES: &[&str] = &[
+    "tbl_hdr_header",
+    "tbl_pa_airports",
+    "tbl_pg_runways",
+    "tbl_d_vhfnavaids",
+    "tbl_db_enroute_ndbnavaids",
+    "tbl_ea_enroute_waypoints",
+    "tbl_er_enroute_airways",
+    "tbl_pd_sids",
+    "tbl_pe_stars",
+    "tbl_pf_iaps",
+];
+
+/// The range around a position to search for runways in, must be longer than the longest runway
+const RUNWAY_SEARCH_RANGE: NauticalMiles = 3.5;
+/// The maximum difference between the heading of an aircraft and a runway for it to be considered lined up
+const RUNWAY_HEADING_TOLERANCE: Degrees = 20.0;
+
 /// The global exported database state
 pub static DATABASE_STATE: Lazy<Mutex<DatabaseState>> =
     Lazy::new(|| Mutex::new(DatabaseState::new()));
@@ -64,7 +119,7 @@ fn get_bundled_db() -> Result<Option<DatabaseDistributionInfo>> {
     let Some(cycle_file_name) = bundled_entries
         .iter()
         .filter_map(|e| e.file_name().to_str().map(|s| s.to_owned()))
-        .find(|e| *e == String::from("cycle.json"))
+        .find(|e| e == "cycle.json")
     else {
         return Ok(None);
     };
@@ -84,14 +139,215 @@ fn get_bundled_db() -> Result<Option<DatabaseDistributionInfo>> {
     )?))
 }
 
+/// Verify the navigation data in the staging folder before it is installed.
+///
+/// This checks the integrity of the database, that all of the `REQUIRED_TABLES` are present and that the cycle in the
+/// `cycle.json` matches the header of the database.
+///
+/// The integrity is checked with `PRAGMA quick_check` one table at a time, yielding back to the sim between tables so
+/// that a large database doesn't block it. The files inside a zip are already verified against the CRC32 stored in
+/// the zip as they are extracted, so the package doesn't carry a separate checksum manifest.
+pub async fn verify_staged_navigation_data() -> Result<()> {
+    let cycle_info = CycleInfo::from_path(Path::new(WORK_STAGING_CYCLE_JSON_PATH))?;
+
+    let conn = Connection::open_with_flags(
+        WORK_STAGING_DB_PATH,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )?;
+    conn.execute_batch("PRAGMA temp_store = MEMORY")?;
+
+    let tables = conn
+        .prepare("SELECT name FROM sqlite_master WHERE type = 'table'")?
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    for table in &tables {
+        let integrity = conn.query_row(
+            &format!("PRAGMA quick_check(\"{}\")", table.replace('"', "\"\"")),
+            [],
+            |row| row.get::<_, String>(0),
+        )?;
+        if integrity != "ok" {
+            return Err(anyhow!(
+                "navigation data failed integrity check: {integrity}"
+            ));
+        }
+
+        futures_lite::future::yield_now().await;
+    }
+
+    for table in REQUIRED_TABLES {
+        if !tables.iter().any(|name| name == table) {
+            return Err(anyhow!("navigation data is missing table {table}"));
+        }
+    }
+
+    let mut header_stmt = conn.prepare("SELECT * FROM tbl_hdr_header")?;
+    let header = utils::fetch_row::<sql::Header>(&mut header_stmt, params![])?;
+    if header.cycle != cycle_info.cycle {
+        return Err(anyhow!(
+            "cycle.json is for cycle {}, but the database is for cycle {}",
+            cycle_info.cycle,
+            header.cycle
+        ));
+    }
+
+    Ok(())
+}
+
+/// Get the ids of the bundled navigation data which has been activated before
+fn activated_bundled_ids() -> Vec<String> {
+    File::open(WORK_ACTIVATED_BUNDLED_IDS_PATH)
+        .ok()
+        .and_then(|file| serde_json::from_reader(file).ok())
+        .unwrap_or_default()
+}
+
+/// Remember that bundled navigation data has been activated, so that it isn't activated again on its own
+///
+/// * `id` - The id of the bundled navigation data
+fn add_activated_bundled_id(id: String) -> Result<()> {
+    let mut ids = activated_bundled_ids();
+    if !ids.contains(&id) {
+        ids.push(id);
+    }
+
+    let file = File::create(WORK_ACTIVATED_BUNDLED_IDS_PATH)?;
+    serde_json::to_writer(file, &ids)
+        .map_err(|e| anyhow!("error occurred writing activated bundled ids: {e}"))
+}
+
+/// Get the path to the folder of an inactive set of navigation data in the library
+///
+/// * `id` - The id of the navigation data
+fn library_folder(id: &str) -> String {
+    format!("{WORK_LIBRARY_FOLDER}/{id}")
+}
+
+/// Create the cycle info JSON in the staging folder from the header of the staged database, if the package it was
+/// extracted from only contained the database
+pub fn ensure_staged_cycle_info() -> Result<()> {
+    if fs::metadata(WORK_STAGING_CYCLE_JSON_PATH).is_ok() {
+        return Ok(());
+    }
+
+    let conn = Connection::open_with_flags(
+        WORK_STAGING_DB_PATH,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )?;
+
+    let mut header_stmt = conn.prepare("SELECT * FROM tbl_hdr_header")?;
+    let header = utils::fetch_row::<sql::Header>(&mut header_stmt, params![])?;
+
+    CycleInfo::from_header(header)?.write_to_path(Path::new(WORK_STAGING_CYCLE_JSON_PATH))
+}
+
+/// Move the active navigation data into the backup folder, and the navigation data in `folder` into its place.
+///
+/// The folders are swapped with renames, so the active navigation data is never partially overwritten. Nothing is
+/// changed if the active navigation data can't be moved, and the backup is restored if the new navigation data can't
+/// be moved.
+///
+/// * `folder` - The folder containing the navigation data to activate
+fn swap_in_navigation_data(folder: &str) -> Result<()> {
+    let _ = fs::remove_dir_all(WORK_BACKUP_FOLDER);
+
+    // There is no active navigation data to back up on the first install
+    if let Err(err) = fs::rename(WORK_NAVIGATION_DATA_FOLDER, WORK_BACKUP_FOLDER) {
+        if err.kind() != ErrorKind::NotFound {
+            return Err(err.into());
+        }
+    }
+
+    if let Err(err) = fs::rename(folder, WORK_NAVIGATION_DATA_FOLDER) {
+        restore_backup_navigation_data()?;
+
+        return Err(err.into());
+    }
+
+    Ok(())
+}
+
+/// Restore the navigation data which was installed before the last swap, if there was any. The active navigation data
+/// is left in place if there is no backup to replace it with.
+fn restore_backup_navigation_data() -> Result<()> {
+    if fs::metadata(WORK_BACKUP_FOLDER).is_ok() {
+        let _ = fs::remove_dir_all(WORK_NAVIGATION_DATA_FOLDER);
+        fs::rename(WORK_BACKUP_FOLDER, WORK_NAVIGATION_DATA_FOLDER)?;
+    }
+
+    Ok(())
+}
+
+/// Move the navigation data which was active before the last swap into the library, so it can be activated again
+/// later.
+///
+/// A copy in the library of the now active navigation data is removed, so every id only exists once.
+fn archive_backup_navigation_data() -> Result<()> {
+    let Ok(cycle_info) = CycleInfo::from_path(Path::new(WORK_BACKUP_CYCLE_JSON_PATH)) else {
+        let _ = fs::remove_dir_all(WORK_BACKUP_FOLDER);
+        return Ok(());
+    };
+
+    // Ensure the library folder exists (ignore the result as it will return an error if it already exists)
+    let _ = fs::create_dir_all(WORK_LIBRARY_FOLDER);
+
+    let archive_folder = library_folder(&cycle_info.id());
+    let _ = fs::remove_dir_all(&archive_folder);
+    fs::rename(WORK_BACKUP_FOLDER, &archive_folder)?;
+
+    if let Ok(active) = CycleInfo::from_path(Path::new(WORK_CYCLE_JSON_PATH)) {
+        let _ = fs::remove_dir_all(library_folder(&active.id()));
+    }
+
+    Ok(())
+}
+
+/// Install the verified navigation data in the staging folder without a database connection, for when the database
+/// state has not been initialized yet (it will load the new navigation data once it is)
+pub fn install_staged_navigation_data_files() -> Result<()> {
+    let old = CycleInfo::from_path(Path::new(WORK_CYCLE_JSON_PATH)).ok();
+
+    swap_in_navigation_data(WORK_STAGING_FOLDER)?;
+
+    archive_backup_navigation_data()?;
+
+    send_navigation_data_changed(old)
+}
+
+/// Let every gauge know that the installed navigation data has changed, so they can invalidate anything they cached
+///
+/// * `old` - The cycle info of the navigation data which was installed before the change, if there was any
+fn send_navigation_data_changed(old: Option<CycleInfo>) -> Result<()> {
+    let new = CycleInfo::from_path(Path::new(WORK_CYCLE_JSON_PATH)).ok();
+
+    InterfaceEvent::send_navigation_data_changed_event(NavigationDataChangedEvent { old, new })
+}
+
+/// Where a set of navigation data was installed from
+#[derive(Serialize, Deserialize, Default, Clone, Copy, PartialEq)]
+pub enum NavigationDataSource {
+    /// Shipped with the aircraft in the bundled data folder
+    Bundled,
+    /// Downloaded through `DownloadNavigationData`
+    #[default]
+    Downloaded,
+    /// Installed from a file which was placed on disk by the aircraft or the user
+    Sideloaded,
+}
+
 /// The struct representation of the cycle info JSON
-#[derive(Deserialize)]
+#[derive(Serialize, Deserialize)]
 pub struct CycleInfo {
     pub cycle: String,
     pub revision: String,
     pub format: String,
     #[serde(rename = "validityPeriod")]
     pub validity_period: String,
+    /// This is not part of the distributed cycle info JSON, so it is only present once the navigation data has been
+    /// installed from somewhere other than a download
+    #[serde(default)]
+    pub source: NavigationDataSource,
 }
 
 impl CycleInfo {
@@ -104,6 +360,50 @@ impl CycleInfo {
         serde_json::from_reader(&mut file)
             .map_err(|e| anyhow!("error occurred reading cycle.json: {e}"))
     }
+
+    /// Get the id which identifies this navigation data in the library
+    pub fn id(&self) -> String {
+        format!("{}-{}-{}", self.format, self.cycle, self.revision)
+    }
+
+    /// Create from the header of a database
+    ///
+    /// * `header` - The header of the database
+    fn from_header(header: sql::Header) -> Result<Self> {
+        let (from, to) = parse_from_to(header.effective_fromto)?;
+
+        // The cycle info JSON uses dates in the format `YYYY-MM-DD` rather than `DD-MM-YYYY`
+        let to_iso_date = |date: String| date.split('-').rev().collect::<Vec<_>>().join("-");
+
+        Ok(Self {
+            cycle: header.cycle,
+            revision: header.revision,
+            format: DATABASE_FORMAT.to_owned(),
+            validity_period: format!("{}/{}", to_iso_date(from), to_iso_date(to)),
+            source: NavigationDataSource::default(),
+        })
+    }
+
+    /// Write to a path
+    ///
+    /// * `path` - The path to write to
+    pub fn write_to_path(&self, path: &Path) -> Result<()> {
+        let file = File::create(path)?;
+
+        serde_json::to_writer(file, self)
+            .map_err(|e| anyhow!("error occurred writing cycle.json: {e}"))
+    }
+}
+
+/// A set of navigation data which is installed in the work folder
+#[derive(Serialize)]
+pub struct InstalledNavigationData {
+    /// The id to pass to `ActivateNavigationData` or `DeleteNavigationData`
+    id: String,
+    #[serde(flatten)]
+    cycle_info: CycleInfo,
+    /// Whether this is the navigation data which queries are served from
+    active: bool,
 }
 
 /// A pair of a cycle info JSON and the corresponding SQLite database.
@@ -136,6 +436,8 @@ impl DatabaseDistributionInfo {
 #[derive(Default)]
 pub struct DatabaseState {
     database: Option<Connection>,
+    /// The boxes containing each FIR and UIR boundary of the open database, found on the first center frequency lookup
+    fir_uir_bounds: OnceCell<Vec<FirUirBounds>>,
 }
 
 impl DatabaseState {
@@ -166,10 +468,20 @@ impl DatabaseState {
             DatabaseDistributionInfo::new(Path::new(WORK_CYCLE_JSON_PATH), Path::new(WORK_DB_PATH))
                 .ok();
 
+        // Bundled navigation data is only activated on its own once. After that the user may have switched away from
+        // it or deleted it, which should stick. Bundled navigation data in the library was activated before the ids
+        // were remembered
+        let activated_bundled_ids = activated_bundled_ids();
+        let bundled_distribution = bundled_distribution.filter(|bundled| {
+            let id = bundled.cycle_info.id();
+
+            !activated_bundled_ids.contains(&id) && fs::metadata(library_folder(&id)).is_err()
+        });
+
         // Find the most recent distribution
         let latest = [downloaded_distribution, bundled_distribution]
             .into_iter()
-            .filter_map(|d| d)
+            .flatten()
             .reduce(|a, b| {
                 // First, compare by cycle number
                 match a
@@ -202,21 +514,44 @@ impl DatabaseState {
             return Ok(());
         };
 
-        // Ensure parent folder exists (ignore the result as it will return an error if it already exists)
-        let _ = fs::create_dir_all(WORK_NAVIGATION_DATA_FOLDER);
+        // The bundled navigation data is copied into the library and activated from there, so the navigation data it
+        // replaces is kept in the library. The cycle info is marked as bundled so it can be told apart from a download
+        let replaced = latest.cycle_info_path != Path::new(WORK_CYCLE_JSON_PATH);
+        let old = CycleInfo::from_path(Path::new(WORK_CYCLE_JSON_PATH)).ok();
 
-        // Ensure files get copied over
-        if latest.cycle_info_path != PathBuf::from(WORK_CYCLE_JSON_PATH) {
-            fs::copy(&latest.cycle_info_path, WORK_CYCLE_JSON_PATH)?;
-        }
-        if latest.db_path != PathBuf::from(WORK_DB_PATH) {
-            fs::copy(&latest.db_path, WORK_DB_PATH)?;
+        let id = latest.cycle_info.id();
+
+        if replaced {
+            let folder = library_folder(&id);
+            let _ = fs::remove_dir_all(&folder);
+            fs::create_dir_all(&folder)?;
+
+            CycleInfo {
+                source: NavigationDataSource::Bundled,
+                ..latest.cycle_info
+            }
+            .write_to_path(Path::new(&format!("{folder}/cycle.json")))?;
+            fs::copy(&latest.db_path, format!("{folder}/db.s3db"))?;
+
+            swap_in_navigation_data(&folder)?;
         }
 
+        // The bundled navigation data is active even if the previous one can't be archived or it can't be remembered,
+        // so it is still opened
+        let archived = if replaced {
+            archive_backup_navigation_data().and(add_activated_bundled_id(id))
+        } else {
+            Ok(())
+        };
+
         // The only way this can fail (since we know now that the path is valid) is if the file is corrupt, in which case we should report to sentry
         self.open_connection()?;
 
-        return Ok(());
+        if replaced {
+            send_navigation_data_changed(old)?;
+        }
+
+        archived
     }
 
     fn get_database(&self) -> Result<&Connection> {
@@ -247,13 +582,121 @@ impl DatabaseState {
         conn.execute_batch("PRAGMA temp_store = MEMORY")?;
 
         self.database = Some(conn);
+        self.fir_uir_bounds = OnceCell::new();
+
+        Ok(())
+    }
+
+    /// Replace the installed navigation data with the verified navigation data in the staging folder, and reopen the
+    /// connection.
+    ///
+    /// If the new database can't be opened, the previous navigation data is restored and reopened.
+    pub fn install_staged_navigation_data(&mut self) -> Result<()> {
+        self.activate_navigation_data_folder(WORK_STAGING_FOLDER)
+    }
+
+    /// List the navigation data which is installed, both active and in the library
+    pub fn list_installed_navigation_data(&self) -> Result<Vec<InstalledNavigationData>> {
+        let active = CycleInfo::from_path(Path::new(WORK_CYCLE_JSON_PATH))
+            .ok()
+            .map(|cycle_info| InstalledNavigationData {
+                id: cycle_info.id(),
+                cycle_info,
+                active: true,
+            });
+
+        let library = match read_dir(WORK_LIBRARY_FOLDER) {
+            Ok(dir) => dir
+                .filter_map(Result::ok)
+                .filter_map(|entry| CycleInfo::from_path(&entry.path().join("cycle.json")).ok())
+                .map(|cycle_info| InstalledNavigationData {
+                    id: cycle_info.id(),
+                    cycle_info,
+                    active: false,
+                })
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+
+        Ok(active.into_iter().chain(library).collect())
+    }
+
+    /// Activate navigation data from the library, moving the currently active navigation data into the library
+    ///
+    /// * `id` - The id of the navigation data to activate
+    pub fn activate_navigation_data(&mut self, id: &str) -> Result<()> {
+        if CycleInfo::from_path(Path::new(WORK_CYCLE_JSON_PATH))
+            .is_ok_and(|active| active.id() == id)
+        {
+            return Ok(());
+        }
+
+        let folder = library_folder(id);
+        if fs::metadata(&folder).is_err() {
+            return Err(anyhow!("No installed navigation data with id {id}"));
+        }
+
+        self.activate_navigation_data_folder(&folder)
+    }
+
+    /// Delete navigation data from the library. The active navigation data can't be deleted
+    ///
+    /// * `id` - The id of the navigation data to delete
+    pub fn delete_navigation_data(&self, id: &str) -> Result<()> {
+        if CycleInfo::from_path(Path::new(WORK_CYCLE_JSON_PATH))
+            .is_ok_and(|active| active.id() == id)
+        {
+            return Err(anyhow!("Can't delete the active navigation data"));
+        }
+
+        let folder = library_folder(id);
+        if fs::metadata(&folder).is_err() {
+            return Err(anyhow!("No installed navigation data with id {id}"));
+        }
+
+        fs::remove_dir_all(folder)?;
 
         Ok(())
     }
 
+    /// Replace the active navigation data with the navigation data in `folder`, and reopen the connection. The
+    /// previously active navigation data is moved into the library.
+    ///
+    /// If the new database can't be opened, it is moved back into `folder` and the previous navigation data is restored
+    /// and reopened.
+    ///
+    /// * `folder` - The folder containing the navigation data to activate
+    fn activate_navigation_data_folder(&mut self, folder: &str) -> Result<()> {
+        let old = CycleInfo::from_path(Path::new(WORK_CYCLE_JSON_PATH)).ok();
+
+        self.close_connection()?;
+
+        if let Err(err) = swap_in_navigation_data(folder) {
+            let _ = self.open_connection();
+
+            return Err(err);
+        }
+
+        if let Err(err) = self.open_connection() {
+            // Move the navigation data which failed to open back to where it came from, so it isn't lost
+            let _ = fs::rename(WORK_NAVIGATION_DATA_FOLDER, folder);
+            restore_backup_navigation_data()?;
+            let _ = self.open_connection();
+
+            return Err(err);
+        }
+
+        // The new navigation data is active even if the previous one can't be archived, so the change is always sent
+        let archived = archive_backup_navigation_data();
+
+        send_navigation_data_changed(old)?;
+
+        archived
+    }
+
     pub fn get_cycle_info(&self) -> Result<CycleInfo> {
         // The WORK_CYCLE_JSON_PATH is the "master" cycle JSON path.
-        return CycleInfo::from_path(Path::new(WORK_CYCLE_JSON_PATH));
+        CycleInfo::from_path(Path::new(WORK_CYCLE_JSON_PATH))
     }
 
     pub fn execute_sql_query(&self, sql: &str, params: &Vec<String>) -> Result<Value> {
@@ -318,6 +761,79 @@ impl DatabaseState {
         Ok(Airport::from(airport_data))
     }
 
+    pub fn find_airports(&self, search: &AirportSearch) -> Result<Vec<Airport>> {
+        let conn = self.get_database()?;
+
+        let (where_string, params) = search.to_where();
+
+        let mut stmt = conn.prepare(
+            format!(
+                "SELECT * FROM tbl_pa_airports WHERE {where_string} ORDER BY airport_identifier LIMIT {}",
+                search.limit
+            )
+            .as_str(),
+        )?;
+
+        let airports_data =
+            utils::fetch_rows::<sql::Airports>(&mut stmt, params_from_iter(params))?;
+
+        Ok(airports_data.into_iter().map(Airport::from).collect())
+    }
+
+    pub fn get_airport_by_iata(&self, iata: &str) -> Result<Airport> {
+        let conn = self.get_database()?;
+
+        // Prefer airports where the code is the IATA identifier over the 3 letter identifier
+        let mut stmt = conn.prepare(
+            "SELECT * FROM tbl_pa_airports WHERE ata_iata_code = (?1) COLLATE NOCASE OR \
+             airport_identifier_3letter = (?1) COLLATE NOCASE ORDER BY ata_iata_code = (?1) COLLATE NOCASE DESC",
+        )?;
+
+        let airport_data = utils::fetch_row::<sql::Airports>(&mut stmt, params![iata])?;
+
+        Ok(Airport::from(airport_data))
+    }
+
+    /// Gets an airport along with the sections listed in `include`, so that all of the data for an airport can be
+    /// loaded in a single call. Procedures are only included as summaries, without their legs, and only if they apply to
+    /// `aircraft_category` when it is given.
+    pub fn get_airport_details(
+        &self,
+        ident: &str,
+        include: &[AirportDetailsInclude],
+        aircraft_category: &Option<AircraftCategory>,
+    ) -> Result<AirportDetails> {
+        let includes = |section| include.contains(&section);
+
+        Ok(AirportDetails {
+            airport: self.get_airport(ident)?,
+            runways: includes(AirportDetailsInclude::Runways)
+                .then(|| self.get_runways_at_airport(ident))
+                .transpose()?,
+            communications: includes(AirportDetailsInclude::Communications)
+                .then(|| self.get_communications_at_airport(ident, &None, &None, &None))
+                .transpose()?,
+            gates: includes(AirportDetailsInclude::Gates)
+                .then(|| self.get_gates_at_airport(ident))
+                .transpose()?,
+            gls_navaids: includes(AirportDetailsInclude::GlsNavaids)
+                .then(|| self.get_gls_navaids_at_airport(ident))
+                .transpose()?,
+            path_points: includes(AirportDetailsInclude::PathPoints)
+                .then(|| self.get_path_points_at_airport(ident))
+                .transpose()?,
+            departures: includes(AirportDetailsInclude::Departures)
+                .then(|| self.get_departure_summaries_at_airport(ident, aircraft_category))
+                .transpose()?,
+            arrivals: includes(AirportDetailsInclude::Arrivals)
+                .then(|| self.get_arrival_summaries_at_airport(ident, aircraft_category))
+                .transpose()?,
+            approaches: includes(AirportDetailsInclude::Approaches)
+                .then(|| self.get_approach_summaries_at_airport(ident, aircraft_category))
+                .transpose()?,
+        })
+    }
+
     pub fn get_waypoints(&self, ident: &str) -> Result<Vec<Waypoint>> {
         let conn = self.get_database()?;
 
@@ -425,6 +941,7 @@ impl DatabaseState {
         &self,
         center: &Coordinates,
         range: &NauticalMiles,
+        type_filter: &Option<WaypointTypeFilter>,
     ) -> Result<Vec<Waypoint>> {
         let conn = self.get_database()?;
 
@@ -446,6 +963,11 @@ impl DatabaseState {
             .chain(terminal_data)
             .map(Waypoint::from)
             .filter(|waypoint| waypoint.location.distance_to(center) <= *range)
+            .filter(|waypoint| {
+                type_filter
+                    .as_ref()
+                    .is_none_or(|type_filter| type_filter.matches(waypoint))
+            })
             .collect())
     }
 
@@ -499,6 +1021,125 @@ impl DatabaseState {
             .collect())
     }
 
+    /// Fetches every navaid from the VHF, NDB and ILS tables which matches a condition on the navaid columns and
+    /// the localizer columns respectively
+    fn fetch_radio_navaids(
+        &self,
+        navaid_where: &str,
+        llz_where: &str,
+        params: &[&dyn ToSql],
+    ) -> Result<Vec<RadioNavaid>> {
+        let conn = self.get_database()?;
+
+        let mut vhf_stmt = conn
+            .prepare(format!("{VHF_NAVAIDS_WITH_ELEVATION_QUERY} WHERE {navaid_where}").as_str())?;
+        let mut enroute_ndb_stmt = conn.prepare(
+            format!("SELECT * FROM tbl_db_enroute_ndbnavaids WHERE {navaid_where}").as_str(),
+        )?;
+        let mut terminal_ndb_stmt = conn.prepare(
+            format!("SELECT * FROM tbl_pn_terminal_ndbnavaids WHERE {navaid_where}").as_str(),
+        )?;
+        let mut ils_stmt = conn.prepare(
+            format!("SELECT * FROM tbl_pi_localizers_glideslopes WHERE {llz_where}").as_str(),
+        )?;
+
+        let vhf_data = utils::fetch_rows::<sql::VhfNavaids>(&mut vhf_stmt, params)?;
+        let enroute_ndb_data = utils::fetch_rows::<sql::NdbNavaids>(&mut enroute_ndb_stmt, params)?;
+        let terminal_ndb_data =
+            utils::fetch_rows::<sql::NdbNavaids>(&mut terminal_ndb_stmt, params)?;
+        let ils_data = utils::fetch_rows::<sql::LocalizersGlideslopes>(&mut ils_stmt, params)?;
+
+        Ok(vhf_data
+            .into_iter()
+            .map(|navaid| RadioNavaid::Vhf(navaid.into()))
+            .chain(
+                enroute_ndb_data
+                    .into_iter()
+                    .chain(terminal_ndb_data)
+                    .map(|navaid| RadioNavaid::Ndb(navaid.into())),
+            )
+            .chain(
+                ils_data
+                    .into_iter()
+                    .map(|navaid| RadioNavaid::Ils(navaid.into())),
+            )
+            .collect())
+    }
+
+    /// Gets the navaids which transmit on a frequency, nearest to `near` first.
+    ///
+    /// The frequency is in megahertz for VHF navaids and localizers, and in kilohertz for NDBs.
+    pub fn get_navaids_by_frequency(
+        &self,
+        frequency: &f64,
+        near: &Coordinates,
+        max_results: &Option<usize>,
+    ) -> Result<Vec<NavaidWithDistance>> {
+        let mut navaids = self
+            .fetch_radio_navaids(
+                "ABS(navaid_frequency - (?1)) < 0.001",
+                "ABS(llz_frequency - (?1)) < 0.001",
+                params![frequency],
+            )?
+            .into_iter()
+            .map(|navaid| NavaidWithDistance::new(navaid, near))
+            .collect::<Vec<_>>();
+
+        navaids.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+
+        if let Some(max_results) = max_results {
+            navaids.truncate(*max_results);
+        }
+
+        Ok(navaids)
+    }
+
+    /// Gets the navaids which can be received by an aircraft at `position` and `altitude`, nearest first
+    pub fn get_receivable_navaids(
+        &self,
+        position: &Coordinates,
+        altitude: &Feet,
+    ) -> Result<Vec<NavaidWithDistance>> {
+        let navaid_where = utils::range_query_where(position, MAX_RECEPTION_RANGE, "navaid");
+        let llz_where = utils::range_query_where(position, MAX_RECEPTION_RANGE, "llz");
+
+        let mut navaids = self
+            .fetch_radio_navaids(&navaid_where, &llz_where, &[])?
+            .into_iter()
+            .filter(|navaid| navaid.is_receivable(position, *altitude))
+            .map(|navaid| NavaidWithDistance::new(navaid, position))
+            .collect::<Vec<_>>();
+
+        navaids.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+
+        Ok(navaids)
+    }
+
+    /// Gets the DME pairs and VOR/DME which give the best radio position update for an aircraft at `position` and
+    /// `altitude`
+    pub fn get_nav_update_candidates(
+        &self,
+        position: &Coordinates,
+        altitude: &Feet,
+        count: &usize,
+    ) -> Result<NavUpdateCandidates> {
+        let conn = self.get_database()?;
+
+        let where_string = utils::range_query_where(position, MAX_RECEPTION_RANGE, "navaid");
+
+        let mut stmt = conn
+            .prepare(format!("{VHF_NAVAIDS_WITH_ELEVATION_QUERY} WHERE {where_string}").as_str())?;
+
+        let navaids_data = utils::fetch_rows::<sql::VhfNavaids>(&mut stmt, [])?;
+
+        Ok(NavUpdateCandidates::new(
+            navaids_data.into_iter().map(VhfNavaid::from).collect(),
+            position,
+            *altitude,
+            *count,
+        ))
+    }
+
     pub fn get_airways_in_range(
         &self,
         center: &Coordinates,
@@ -614,6 +1255,195 @@ impl DatabaseState {
             .collect())
     }
 
+    /// Gets the enroute communications of an FIR or UIR, along with the communications of every airport within it
+    pub fn get_communications_for_fir(&self, fir_ident: &str) -> Result<Vec<Communication>> {
+        let conn = self.get_database()?;
+
+        let mut enroute_stmt =
+            conn.prepare("SELECT * FROM tbl_ev_enroute_communication WHERE fir_rdo_ident = (?1)")?;
+        let mut boundary_stmt = conn.prepare(
+            "SELECT * FROM tbl_uf_firuir WHERE fir_uir_identifier = (?1) \
+             ORDER BY fir_uir_indicator, seqno",
+        )?;
+
+        let enroute_data =
+            utils::fetch_rows::<sql::EnrouteCommunication>(&mut enroute_stmt, params![fir_ident])?;
+        let boundary_data =
+            utils::fetch_rows::<sql::FirUir>(&mut boundary_stmt, params![fir_ident])?;
+
+        let mut communications = enroute_data
+            .into_iter()
+            .map(Communication::from)
+            .collect::<Vec<_>>();
+
+        // A UIR can share its identifier with an FIR, in which case the boundary of the FIR is used
+        if let Some(boundary) = map_fir_uir_boundaries(boundary_data)
+            .into_iter()
+            .min_by_key(|boundary| boundary.indicator.as_deref() == Some("U"))
+        {
+            // Use the bounding box of the boundary to limit the airports which have to be checked
+            let (south_west, north_east) = boundary.bounds();
+
+            let mut terminal_stmt = conn.prepare(
+                "SELECT * FROM tbl_pv_airport_communication WHERE latitude BETWEEN (?1) AND (?2) AND \
+                 longitude BETWEEN (?3) AND (?4)",
+            )?;
+
+            let terminal_data = utils::fetch_rows::<sql::AirportCommunication>(
+                &mut terminal_stmt,
+                params![
+                    south_west.lat,
+                    north_east.lat,
+                    south_west.long,
+                    north_east.long
+                ],
+            )?;
+
+            communications.extend(
+                terminal_data
+                    .into_iter()
+                    .map(Communication::from)
+                    .filter(|communication| boundary.contains(&communication.location)),
+            );
+        }
+
+        Ok(communications)
+    }
+
+    /// Gets the airport and enroute communications which transmit on a frequency, nearest to `near` first.
+    ///
+    /// If `range` is set, only communications within `range` of `near` are returned.
+    pub fn get_communications_by_frequency(
+        &self,
+        frequency: &f64,
+        near: &Coordinates,
+        range: &Option<NauticalMiles>,
+        max_results: &Option<usize>,
+    ) -> Result<Vec<Communication>> {
+        let conn = self.get_database()?;
+
+        let where_string = match range {
+            Some(range) => format!(
+                "ABS(communication_frequency - (?1)) < 0.001 AND {}",
+                utils::range_query_where(near, *range, "")
+            ),
+            None => "ABS(communication_frequency - (?1)) < 0.001".to_string(),
+        };
+
+        let mut enroute_stmt = conn.prepare(
+            format!("SELECT * FROM tbl_ev_enroute_communication WHERE {where_string}").as_str(),
+        )?;
+        let mut terminal_stmt = conn.prepare(
+            format!("SELECT * FROM tbl_pv_airport_communication WHERE {where_string}").as_str(),
+        )?;
+
+        let enroute_data =
+            utils::fetch_rows::<sql::EnrouteCommunication>(&mut enroute_stmt, params![frequency])?;
+        let terminal_data =
+            utils::fetch_rows::<sql::AirportCommunication>(&mut terminal_stmt, params![frequency])?;
+
+        let mut communications = enroute_data
+            .into_iter()
+            .map(Communication::from)
+            .chain(terminal_data.into_iter().map(Communication::from))
+            .filter(|communication| {
+                range.is_none_or(|range| communication.location.distance_to(near) <= range)
+            })
+            .collect::<Vec<_>>();
+
+        communications.sort_by(|a, b| {
+            a.location
+                .distance_to(near)
+                .total_cmp(&b.location.distance_to(near))
+        });
+
+        if let Some(max_results) = max_results {
+            communications.truncate(*max_results);
+        }
+
+        Ok(communications)
+    }
+
+    /// Gets the center frequency responsible for a position.
+    ///
+    /// This finds the FIRs and UIRs whose boundary contains the position, and returns the area control communication
+    /// of those with the transmitter nearest to the position. FIRs are preferred over UIRs.
+    pub fn get_center_frequency_at(&self, position: &Coordinates) -> Result<Option<Communication>> {
+        let conn = self.get_database()?;
+
+        // An FIR and a UIR can share an identifier, so they are grouped by their indicator too. The boxes are built
+        // from the full boundaries so that circles, whose position is only stored as the arc origin, are included
+        let bounds = self.fir_uir_bounds.get_or_try_init(|| -> Result<_> {
+            let mut bounds_stmt = conn.prepare(
+                "SELECT * FROM tbl_uf_firuir ORDER BY fir_uir_identifier, fir_uir_indicator, seqno",
+            )?;
+
+            let boundary_data = utils::fetch_rows::<sql::FirUir>(&mut bounds_stmt, [])?;
+
+            Ok(map_fir_uir_boundaries(boundary_data)
+                .iter()
+                .map(FirUirBounds::from)
+                .collect::<Vec<_>>())
+        })?;
+
+        let mut boundary_stmt = conn.prepare(
+            "SELECT * FROM tbl_uf_firuir WHERE fir_uir_identifier = (?1) \
+             AND fir_uir_indicator IS (?2) ORDER BY seqno",
+        )?;
+        let mut communications_stmt =
+            conn.prepare("SELECT * FROM tbl_ev_enroute_communication WHERE fir_rdo_ident = (?1)")?;
+
+        let mut communications = Vec::new();
+
+        for candidate in bounds.iter().filter(|bounds| bounds.contains(position)) {
+            let boundary_data = utils::fetch_rows::<sql::FirUir>(
+                &mut boundary_stmt,
+                params![candidate.ident, candidate.indicator],
+            )?;
+
+            let is_uir = candidate.indicator.as_deref() == Some("U");
+
+            if !map_fir_uir_boundaries(boundary_data)
+                .iter()
+                .any(|boundary| boundary.contains(position))
+            {
+                continue;
+            }
+
+            let communications_data = utils::fetch_rows::<sql::EnrouteCommunication>(
+                &mut communications_stmt,
+                params![candidate.ident],
+            )?;
+
+            communications.extend(
+                communications_data
+                    .into_iter()
+                    .map(Communication::from)
+                    .filter(|communication| {
+                        matches!(
+                            communication.communication_type,
+                            CommunicationType::AreaControlCenter
+                                | CommunicationType::UpperAreaControl
+                                | CommunicationType::Control
+                                | CommunicationType::Radio
+                        )
+                    })
+                    .map(|communication| (is_uir, communication)),
+            );
+        }
+
+        Ok(communications
+            .into_iter()
+            .min_by(|(a_is_uir, a), (b_is_uir, b)| {
+                a_is_uir.cmp(b_is_uir).then(
+                    a.location
+                        .distance_to(position)
+                        .total_cmp(&b.location.distance_to(position)),
+                )
+            })
+            .map(|(_, communication)| communication))
+    }
+
     pub fn get_runways_at_airport(&self, airport_ident: &str) -> Result<Vec<RunwayThreshold>> {
         let conn = self.get_database()?;
 
@@ -625,50 +1455,387 @@ impl DatabaseState {
         Ok(runways_data.into_iter().map(Into::into).collect())
     }
 
-    pub fn get_departures_at_airport(&self, airport_ident: &str) -> Result<Vec<Departure>> {
+    pub fn get_runway_pairs_at_airport(&self, airport_ident: &str) -> Result<Vec<Runway>> {
+        let conn = self.get_database()?;
+
+        let mut stmt =
+            conn.prepare("SELECT * FROM tbl_pg_runways WHERE airport_identifier = (?1)")?;
+
+        let runways_data = utils::fetch_rows::<sql::Runways>(&mut stmt, params![airport_ident])?;
+
+        Ok(map_runways(runways_data))
+    }
+
+    /// Gets the wind components of every runway threshold at an airport, ranked from most to least suitable.
+    ///
+    /// Runways are ranked by their tailwind, then by their crosswind (both including gusts) and finally by their length.
+    pub fn get_runway_components(
+        &self,
+        airport_ident: &str,
+        wind: &Wind,
+        min_length: &Option<Feet>,
+        surfaces: &Option<Vec<RunwaySurface>>,
+    ) -> Result<Vec<RunwayWindComponents>> {
+        let mut wind = *wind;
+
+        if let WindReference::Magnetic = wind.reference {
+            let airport = self.get_airport(airport_ident)?;
+
+            wind.direction += airport.magnetic_variation.unwrap_or_default();
+        }
+
+        let mut components = self
+            .get_runways_at_airport(airport_ident)?
+            .into_iter()
+            .filter(|runway| min_length.is_none_or(|min_length| runway.length >= min_length))
+            .filter(|runway| {
+                surfaces.as_ref().is_none_or(|surfaces| {
+                    runway
+                        .surface
+                        .is_some_and(|surface| surfaces.contains(&surface))
+                })
+            })
+            .map(|runway| RunwayWindComponents::new(runway, &wind))
+            .collect::<Vec<_>>();
+
+        components.sort_by(|a, b| {
+            a.max_tailwind()
+                .total_cmp(&b.max_tailwind())
+                .then(a.max_crosswind().total_cmp(&b.max_crosswind()))
+                .then(b.runway.length.total_cmp(&a.runway.length))
+        });
+
+        Ok(components)
+    }
+
+    /// Gets the runway threshold which an aircraft at `position` is lined up on, if any.
+    ///
+    /// The aircraft is lined up if it is on the runway surface between the start of the runway (including any
+    /// displaced threshold) and its end, with its `true_heading` within `RUNWAY_HEADING_TOLERANCE` of the runway.
+    pub fn get_runway_at_position(
+        &self,
+        position: &Coordinates,
+        true_heading: &Degrees,
+    ) -> Result<Option<RunwayThreshold>> {
+        let conn = self.get_database()?;
+
+        let where_string = utils::range_query_where(position, RUNWAY_SEARCH_RANGE, "runway");
+
+        let mut stmt =
+            conn.prepare(format!("SELECT * FROM tbl_pg_runways WHERE {where_string}").as_str())?;
+
+        let runways_data = utils::fetch_rows::<sql::Runways>(&mut stmt, [])?;
+
+        Ok(runways_data
+            .into_iter()
+            .filter_map(|runway| {
+                let displaced_distance = utils::feet_to_nautical_miles(
+                    runway.displaced_threshold_distance.unwrap_or_default(),
+                );
+                let runway = RunwayThreshold::from(runway);
+
+                let heading_error =
+                    utils::bearing_difference(runway.true_bearing, *true_heading).abs();
+
+                (runway.is_on_surface(position, displaced_distance)
+                    && heading_error <= RUNWAY_HEADING_TOLERANCE)
+                    .then_some((heading_error, runway))
+            })
+            .min_by(|(a, _), (b, _)| a.total_cmp(b))
+            .map(|(_, runway)| runway))
+    }
+
+    /// Gets the gate nearest to `position`, if there is one within `tolerance`
+    pub fn get_gate_at_position(
+        &self,
+        position: &Coordinates,
+        tolerance: &Meters,
+    ) -> Result<Option<Gate>> {
+        let conn = self.get_database()?;
+
+        let range = utils::meters_to_nautical_miles(*tolerance);
+
+        let where_string = utils::range_query_where(position, range, "gate");
+
+        let mut stmt =
+            conn.prepare(format!("SELECT * FROM tbl_pb_gates WHERE {where_string}").as_str())?;
+
+        let gates_data = utils::fetch_rows::<sql::Gate>(&mut stmt, [])?;
+
+        Ok(gates_data
+            .into_iter()
+            .map(Gate::from)
+            .map(|gate| (gate.location.distance_to(position), gate))
+            .filter(|(distance, _)| *distance <= range)
+            .min_by(|(a, _), (b, _)| a.total_cmp(b))
+            .map(|(_, gate)| gate))
+    }
+
+    pub fn get_departures_at_airport(
+        &self,
+        airport_ident: &str,
+        aircraft_category: &Option<AircraftCategory>,
+    ) -> Result<Vec<Departure>> {
+        self.fetch_departures(airport_ident, None, *aircraft_category)
+    }
+
+    pub fn get_departure_summaries_at_airport(
+        &self,
+        airport_ident: &str,
+        aircraft_category: &Option<AircraftCategory>,
+    ) -> Result<Vec<ProcedureSummary>> {
+        let conn = self.get_database()?;
+
+        let mut runways_stmt =
+            conn.prepare("SELECT * FROM tbl_pg_runways WHERE airport_identifier = (?1)")?;
+
+        let runways_data =
+            utils::fetch_rows::<sql::Runways>(&mut runways_stmt, params![airport_ident])?;
+
+        Ok(map_departure_summaries(
+            self.fetch_procedure_headers("tbl_pd_sids", airport_ident, *aircraft_category)?,
+            &runways_data,
+        ))
+    }
+
+    pub fn get_departure(
+        &self,
+        airport_ident: &str,
+        ident: &str,
+        aircraft_category: &Option<AircraftCategory>,
+    ) -> Result<Departure> {
+        self.fetch_departures(airport_ident, Some(ident), *aircraft_category)?
+            .pop()
+            .ok_or(anyhow!("No departure {ident} found at {airport_ident}"))
+    }
+
+    /// Fetches the departures at an airport which apply to `aircraft_category`, optionally only the departure with the ident
+    /// `procedure_ident`
+    fn fetch_departures(
+        &self,
+        airport_ident: &str,
+        procedure_ident: Option<&str>,
+        aircraft_category: Option<AircraftCategory>,
+    ) -> Result<Vec<Departure>> {
         let conn = self.get_database()?;
 
-        let mut departures_stmt =
-            conn.prepare("SELECT * FROM tbl_pd_sids WHERE airport_identifier = (?1)")?;
+        let mut departures_stmt = conn.prepare(
+            "SELECT * FROM tbl_pd_sids WHERE airport_identifier = (?1) AND ((?2) IS NULL OR procedure_identifier = (?2))",
+        )?;
 
         let mut runways_stmt =
             conn.prepare("SELECT * FROM tbl_pg_runways WHERE airport_identifier = (?1)")?;
 
-        let departures_data =
-            utils::fetch_rows::<sql::Procedures>(&mut departures_stmt, params![airport_ident])?;
+        let departures_data = utils::fetch_rows::<sql::Procedures>(
+            &mut departures_stmt,
+            params![airport_ident, procedure_ident],
+        )?;
         let runways_data =
             utils::fetch_rows::<sql::Runways>(&mut runways_stmt, params![airport_ident])?;
 
-        Ok(map_departures(departures_data, runways_data))
+        Ok(map_departures(
+            filter_aircraft_category(departures_data, aircraft_category),
+            runways_data,
+        ))
     }
 
-    pub fn get_arrivals_at_airport(&self, airport_ident: &str) -> Result<Vec<Arrival>> {
+    pub fn get_arrivals_at_airport(
+        &self,
+        airport_ident: &str,
+        aircraft_category: &Option<AircraftCategory>,
+    ) -> Result<Vec<Arrival>> {
+        self.fetch_arrivals(airport_ident, None, *aircraft_category)
+    }
+
+    pub fn get_arrival_summaries_at_airport(
+        &self,
+        airport_ident: &str,
+        aircraft_category: &Option<AircraftCategory>,
+    ) -> Result<Vec<ProcedureSummary>> {
         let conn = self.get_database()?;
 
-        let mut arrivals_stmt =
-            conn.prepare("SELECT * FROM tbl_pe_stars WHERE airport_identifier = (?1)")?;
+        let mut runways_stmt =
+            conn.prepare("SELECT * FROM tbl_pg_runways WHERE airport_identifier = (?1)")?;
+
+        let runways_data =
+            utils::fetch_rows::<sql::Runways>(&mut runways_stmt, params![airport_ident])?;
+
+        Ok(map_arrival_summaries(
+            self.fetch_procedure_headers("tbl_pe_stars", airport_ident, *aircraft_category)?,
+            &runways_data,
+        ))
+    }
+
+    pub fn get_arrival(
+        &self,
+        airport_ident: &str,
+        ident: &str,
+        aircraft_category: &Option<AircraftCategory>,
+    ) -> Result<Arrival> {
+        self.fetch_arrivals(airport_ident, Some(ident), *aircraft_category)?
+            .pop()
+            .ok_or(anyhow!("No arrival {ident} found at {airport_ident}"))
+    }
+
+    /// Fetches the arrivals at an airport which apply to `aircraft_category`, optionally only the arrival with the ident
+    /// `procedure_ident`
+    fn fetch_arrivals(
+        &self,
+        airport_ident: &str,
+        procedure_ident: Option<&str>,
+        aircraft_category: Option<AircraftCategory>,
+    ) -> Result<Vec<Arrival>> {
+        let conn = self.get_database()?;
+
+        let mut arrivals_stmt = conn.prepare(
+            "SELECT * FROM tbl_pe_stars WHERE airport_identifier = (?1) AND ((?2) IS NULL OR procedure_identifier = (?2))",
+        )?;
 
         let mut runways_stmt =
             conn.prepare("SELECT * FROM tbl_pg_runways WHERE airport_identifier = (?1)")?;
 
-        let arrivals_data =
-            utils::fetch_rows::<sql::Procedures>(&mut arrivals_stmt, params![airport_ident])?;
+        let arrivals_data = utils::fetch_rows::<sql::Procedures>(
+            &mut arrivals_stmt,
+            params![airport_ident, procedure_ident],
+        )?;
         let runways_data =
             utils::fetch_rows::<sql::Runways>(&mut runways_stmt, params![airport_ident])?;
 
-        Ok(map_arrivals(arrivals_data, runways_data))
+        Ok(map_arrivals(
+            filter_aircraft_category(arrivals_data, aircraft_category),
+            runways_data,
+        ))
+    }
+
+    pub fn get_approaches_at_airport(
+        &self,
+        airport_ident: &str,
+        aircraft_category: &Option<AircraftCategory>,
+    ) -> Result<Vec<Approach>> {
+        self.fetch_approaches(airport_ident, None, *aircraft_category)
+    }
+
+    pub fn get_approach_summaries_at_airport(
+        &self,
+        airport_ident: &str,
+        aircraft_category: &Option<AircraftCategory>,
+    ) -> Result<Vec<ProcedureSummary>> {
+        Ok(map_approach_summaries(self.fetch_procedure_headers(
+            "tbl_pf_iaps",
+            airport_ident,
+            *aircraft_category,
+        )?))
+    }
+
+    pub fn get_approach(
+        &self,
+        airport_ident: &str,
+        ident: &str,
+        aircraft_category: &Option<AircraftCategory>,
+    ) -> Result<Approach> {
+        self.fetch_approaches(airport_ident, Some(ident), *aircraft_category)?
+            .pop()
+            .ok_or(anyhow!("No approach {ident} found at {airport_ident}"))
     }
 
-    pub fn get_approaches_at_airport(&self, airport_ident: &str) -> Result<Vec<Approach>> {
+    /// Fetches the approaches at an airport which apply to `aircraft_category`, optionally only the approach with the ident
+    /// `procedure_ident`
+    fn fetch_approaches(
+        &self,
+        airport_ident: &str,
+        procedure_ident: Option<&str>,
+        aircraft_category: Option<AircraftCategory>,
+    ) -> Result<Vec<Approach>> {
         let conn = self.get_database()?;
 
-        let mut approachs_stmt =
-            conn.prepare("SELECT * FROM tbl_pf_iaps WHERE airport_identifier = (?1)")?;
+        let mut approachs_stmt = conn.prepare(
+            "SELECT * FROM tbl_pf_iaps WHERE airport_identifier = (?1) AND ((?2) IS NULL OR procedure_identifier = (?2))",
+        )?;
 
-        let approaches_data =
-            utils::fetch_rows::<sql::Procedures>(&mut approachs_stmt, params![airport_ident])?;
+        let mut path_points_stmt =
+            conn.prepare("SELECT * FROM tbl_pp_pathpoint WHERE airport_identifier = (?1)")?;
+
+        let approaches_data = utils::fetch_rows::<sql::Procedures>(
+            &mut approachs_stmt,
+            params![airport_ident, procedure_ident],
+        )?;
+        let path_points_data =
+            utils::fetch_rows::<sql::Pathpoints>(&mut path_points_stmt, params![airport_ident])?;
 
-        Ok(map_approaches(approaches_data))
+        Ok(map_approaches(
+            filter_aircraft_category(approaches_data, aircraft_category),
+            path_points_data,
+        ))
+    }
+
+    /// Fetches only the columns of the rows of a procedure table which are needed to summarise the procedures at an
+    /// airport which apply to `aircraft_category`
+    fn fetch_procedure_headers(
+        &self,
+        table: &str,
+        airport_ident: &str,
+        aircraft_category: Option<AircraftCategory>,
+    ) -> Result<Vec<sql::ProcedureHeaders>> {
+        let conn = self.get_database()?;
+
+        let mut stmt = conn.prepare(
+            format!(
+                "SELECT aircraft_category, authorization_required, procedure_identifier, rnp, route_type, \
+                 transition_identifier, waypoint_description_code FROM {table} WHERE airport_identifier = (?1)"
+            )
+            .as_str(),
+        )?;
+
+        Ok(
+            utils::fetch_rows::<sql::ProcedureHeaders>(&mut stmt, params![airport_ident])?
+                .into_iter()
+                .filter(|row| {
+                    applies_to_aircraft_category(&row.aircraft_category, aircraft_category)
+                })
+                .collect(),
+        )
+    }
+
+    /// Gets the vertical profile of a procedure assembled from the given transitions.
+    ///
+    /// For approaches, `enroute_transition` is the approach transition and `runway_transition` is ignored.
+    pub fn get_procedure_vertical_profile(
+        &self,
+        airport_ident: &str,
+        kind: &ProcedureKind,
+        ident: &str,
+        runway_transition: &Option<String>,
+        enroute_transition: &Option<String>,
+        aircraft_category: &Option<AircraftCategory>,
+    ) -> Result<VerticalProfile> {
+        let legs_error = || anyhow!("Transition not found in procedure {ident} at {airport_ident}");
+
+        match kind {
+            ProcedureKind::Departure => {
+                let departure = self.get_departure(airport_ident, ident, aircraft_category)?;
+                let legs = departure
+                    .assemble_legs(runway_transition, enroute_transition)
+                    .ok_or_else(legs_error)?;
+
+                Ok(VerticalProfile::new(&legs))
+            }
+            ProcedureKind::Arrival => {
+                let arrival = self.get_arrival(airport_ident, ident, aircraft_category)?;
+                let legs = arrival
+                    .assemble_legs(runway_transition, enroute_transition)
+                    .ok_or_else(legs_error)?;
+
+                Ok(VerticalProfile::new(&legs))
+            }
+            ProcedureKind::Approach => {
+                let approach = self.get_approach(airport_ident, ident, aircraft_category)?;
+                let legs = approach
+                    .assemble_legs(enroute_transition)
+                    .ok_or_else(legs_error)?;
+
+                Ok(VerticalProfile::new(&legs))
+            }
+        }
     }
 
     pub fn get_waypoints_at_airport(&self, airport_ident: &str) -> Result<Vec<Waypoint>> {
@@ -707,7 +1874,18 @@ impl DatabaseState {
         Ok(gates_data.into_iter().map(Gate::from).collect())
     }
 
-    pub fn get_communications_at_airport(&self, airport_ident: &str) -> Result<Vec<Communication>> {
+    /// Gets the communications at an airport.
+    ///
+    /// If `active_at` (seconds since the unix epoch, UTC) is given, only communications which are operating at that
+    /// time are returned. If `position` is given, only communications whose sector contains the position (and
+    /// `altitude`, if given) are returned. Communications without a published schedule or sector are always returned.
+    pub fn get_communications_at_airport(
+        &self,
+        airport_ident: &str,
+        active_at: &Option<u64>,
+        position: &Option<Coordinates>,
+        altitude: &Option<Feet>,
+    ) -> Result<Vec<Communication>> {
         let conn = self.get_database()?;
 
         let mut stmt = conn.prepare(
@@ -717,7 +1895,104 @@ impl DatabaseState {
         let gates_data =
             utils::fetch_rows::<sql::AirportCommunication>(&mut stmt, params![airport_ident])?;
 
-        Ok(gates_data.into_iter().map(Communication::from).collect())
+        let magnetic_variation = match position {
+            Some(_) => self.get_airport(airport_ident)?.magnetic_variation,
+            None => None,
+        }
+        .unwrap_or_default();
+
+        // Several communications are usually sectorized around the same facility, so each is only looked up once
+        let mut facility_locations = HashMap::new();
+        let mut communications = Vec::new();
+
+        for communication in gates_data.into_iter().map(Communication::from) {
+            let active = active_at.is_none_or(|time| {
+                communication
+                    .schedule
+                    .as_ref()
+                    .is_none_or(|schedule| schedule.is_active(time))
+            });
+
+            if !active {
+                continue;
+            }
+
+            if let (Some(position), Some(sector)) = (position, communication.sector) {
+                let facility_location = match &communication.sector_facility {
+                    Some(facility) => {
+                        let key = (
+                            facility.clone(),
+                            communication.sector_facility_icao_code.clone(),
+                        );
+
+                        match facility_locations.get(&key) {
+                            Some(location) => *location,
+                            None => {
+                                let location = self.get_sector_facility_location(
+                                    facility,
+                                    &communication.sector_facility_icao_code,
+                                )?;
+                                facility_locations.insert(key, location);
+                                location
+                            }
+                        }
+                    }
+                    None => None,
+                };
+
+                // The sector is measured from the communication itself if its facility is not known
+                let bearing = facility_location
+                    .unwrap_or(communication.location)
+                    .bearing_to(position);
+
+                if !sector.contains(bearing - magnetic_variation, *altitude) {
+                    continue;
+                }
+            }
+
+            communications.push(communication);
+        }
+
+        Ok(communications)
+    }
+
+    /// Finds the location of the navaid, airport or waypoint which the sectorization of a communication is measured
+    /// from
+    fn get_sector_facility_location(
+        &self,
+        ident: &str,
+        icao_code: &Option<String>,
+    ) -> Result<Option<Coordinates>> {
+        let conn = self.get_database()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT latitude, longitude FROM ( \
+                SELECT COALESCE(navaid_latitude, dme_latitude) AS latitude, \
+                    COALESCE(navaid_longitude, dme_longitude) AS longitude, icao_code \
+                    FROM tbl_d_vhfnavaids WHERE navaid_identifier = (?1) \
+                UNION ALL SELECT navaid_latitude, navaid_longitude, icao_code \
+                    FROM tbl_db_enroute_ndbnavaids WHERE navaid_identifier = (?1) \
+                UNION ALL SELECT navaid_latitude, navaid_longitude, icao_code \
+                    FROM tbl_pn_terminal_ndbnavaids WHERE navaid_identifier = (?1) \
+                UNION ALL SELECT airport_ref_latitude, airport_ref_longitude, icao_code \
+                    FROM tbl_pa_airports WHERE airport_identifier = (?1) \
+                UNION ALL SELECT waypoint_latitude, waypoint_longitude, icao_code \
+                    FROM tbl_ea_enroute_waypoints WHERE waypoint_identifier = (?1) \
+            ) WHERE latitude IS NOT NULL AND longitude IS NOT NULL \
+                AND ((?2) IS NULL OR icao_code = (?2)) LIMIT 1",
+        )?;
+
+        let location = stmt
+            .query_map(params![ident, icao_code], |row| {
+                Ok(Coordinates {
+                    lat: row.get(0)?,
+                    long: row.get(1)?,
+                })
+            })?
+            .next()
+            .transpose()?;
+
+        Ok(location)
     }
 
     pub fn get_gls_navaids_at_airport(&self, airport_ident: &str) -> Result<Vec<GlsNavaid>> {
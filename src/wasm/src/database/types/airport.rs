@@ -1,9 +1,15 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::database::utils::{Coordinates, Degrees, Feet};
 
 use super::{
+    communication::Communication,
     enums::{IfrCapability, RunwaySurfaceCode},
+    gate::Gate,
+    gls_navaid::GlsNavaid,
+    path_point::PathPoint,
+    procedure::ProcedureSummary,
+    runway::RunwayThreshold,
     sql,
 };
 
@@ -89,3 +95,90 @@ impl From<sql::Airports> for Airport {
         }
     }
 }
+
+/// An airport together with the data at it, as returned by `GetAirportDetails`.
+///
+/// Each list is only present when it was requested in the `include` of the call.
+#[serde_with::skip_serializing_none]
+#[derive(Serialize)]
+pub struct AirportDetails {
+    pub airport: Airport,
+    pub runways: Option<Vec<RunwayThreshold>>,
+    pub communications: Option<Vec<Communication>>,
+    pub gates: Option<Vec<Gate>>,
+    pub gls_navaids: Option<Vec<GlsNavaid>>,
+    pub path_points: Option<Vec<PathPoint>>,
+    pub departures: Option<Vec<ProcedureSummary>>,
+    pub arrivals: Option<Vec<ProcedureSummary>>,
+    pub approaches: Option<Vec<ProcedureSummary>>,
+}
+
+#[derive(Deserialize)]
+/// A search for airports. Every criteria which is set must match, text criteria are case insensitive
+pub struct AirportSearch {
+    /// The IATA identifier of the airport, such as `LHR`. This also matches the 3 letter identifier of the airport
+    pub iata: Option<String>,
+    /// Text which the formal name of the airport must contain
+    pub name_contains: Option<String>,
+    /// The city of the airport (v2 only)
+    pub city: Option<String>,
+    /// The country of the airport, either its name or 3 letter code (v2 only)
+    pub country: Option<String>,
+    /// The state of the airport, either its name or 2 letter code (v2 only)
+    pub state: Option<String>,
+    /// The airport type (see Appendix 3.38) (v2 only)
+    pub r#type: Option<String>,
+    /// The maximum number of airports to return
+    pub limit: usize,
+}
+
+/// Escapes the wildcard characters of a `LIKE` pattern, which must then be used with `ESCAPE '\'`
+fn escape_like(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+impl AirportSearch {
+    /// Builds the `WHERE` clause and its parameters for this search
+    pub fn to_where(&self) -> (String, Vec<String>) {
+        let mut conditions = Vec::new();
+        let mut params = Vec::new();
+
+        if let Some(iata) = &self.iata {
+            conditions.push("(ata_iata_code = ? COLLATE NOCASE OR airport_identifier_3letter = ? COLLATE NOCASE)");
+            params.extend([iata.clone(), iata.clone()]);
+        }
+
+        if let Some(name) = &self.name_contains {
+            conditions.push("airport_name LIKE ? ESCAPE '\\'");
+            params.push(format!("%{}%", escape_like(name)));
+        }
+
+        if let Some(city) = &self.city {
+            conditions.push("city = ? COLLATE NOCASE");
+            params.push(city.clone());
+        }
+
+        if let Some(country) = &self.country {
+            conditions.push("(country = ? COLLATE NOCASE OR country_3letter = ? COLLATE NOCASE)");
+            params.extend([country.clone(), country.clone()]);
+        }
+
+        if let Some(state) = &self.state {
+            conditions.push("(state = ? COLLATE NOCASE OR state_2letter = ? COLLATE NOCASE)");
+            params.extend([state.clone(), state.clone()]);
+        }
+
+        if let Some(airport_type) = &self.r#type {
+            conditions.push("airport_type = ?");
+            params.push(airport_type.clone());
+        }
+
+        if conditions.is_empty() {
+            conditions.push("1");
+        }
+
+        (conditions.join(" AND "), params)
+    }
+}
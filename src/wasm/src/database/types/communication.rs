@@ -1,12 +1,218 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
 use serde::Serialize;
 
-use crate::database::utils::Coordinates;
+use crate::database::utils::{Coordinates, Degrees, Feet, Minutes};
 
 use super::{
-    enums::{CommunicationType, FrequencyUnits},
+    enums::{CommunicationType, FrequencyUnits, Weekday},
     sql,
 };
 
+/// Matches a sectorization such as `090270`, `270090 BLW5000` or `000180 3000-10000`
+static SECTORIZATION_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(\d{3})(\d{3})\s*(?:(ABV|BLW)\s*(\d+)|(\d+)\s*-\s*(\d+))?").unwrap()
+});
+
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Monday,
+    Weekday::Tuesday,
+    Weekday::Wednesday,
+    Weekday::Thursday,
+    Weekday::Friday,
+    Weekday::Saturday,
+    Weekday::Sunday,
+];
+
+#[derive(Serialize, Debug, Clone)]
+/// A period in which a communication is operating
+pub struct OperatingPeriod {
+    /// The days on which this period starts
+    pub days: Vec<Weekday>,
+    /// The start of this period in minutes after midnight UTC
+    pub start: Minutes,
+    /// The end of this period in minutes after midnight UTC. If this is before `start`, the period ends on the
+    /// following day
+    pub end: Minutes,
+}
+
+#[derive(Serialize, Debug, Clone)]
+/// The times in which a communication is operating, parsed from its time of operation fields
+pub struct OperatingSchedule {
+    pub periods: Vec<OperatingPeriod>,
+}
+
+impl OperatingSchedule {
+    /// Checks whether or not the communication is operating at `time`, given in seconds since the unix epoch (UTC)
+    pub fn is_active(&self, time: u64) -> bool {
+        let days = time / 86400;
+        // The unix epoch was on a thursday
+        let today = WEEKDAYS[((days + 3) % 7) as usize];
+        let yesterday = WEEKDAYS[((days + 2) % 7) as usize];
+        let minute = ((time % 86400) / 60) as Minutes;
+
+        self.periods.iter().any(|period| {
+            if period.start <= period.end {
+                period.days.contains(&today) && minute >= period.start && minute < period.end
+            } else {
+                (period.days.contains(&today) && minute >= period.start)
+                    || (period.days.contains(&yesterday) && minute < period.end)
+            }
+        })
+    }
+}
+
+/// Parses the days of a time of operation field, such as `MON-FRI`, `SAT,SUN` or `DLY`
+fn map_weekdays(code: &str) -> Option<Vec<Weekday>> {
+    let weekday = |name: &str| {
+        ["MON", "TUE", "WED", "THU", "FRI", "SAT", "SUN"]
+            .iter()
+            .position(|day| name.starts_with(day))
+    };
+
+    let code = code.trim();
+
+    if code.is_empty() || code == "DLY" || code == "DAILY" || code == "ALL" {
+        return Some(WEEKDAYS.to_vec());
+    }
+
+    let mut days = Vec::new();
+
+    for token in code.split([',', ' ']).filter(|token| !token.is_empty()) {
+        match token.split_once('-') {
+            Some((first, last)) => {
+                let first = weekday(first)?;
+                let last = weekday(last)?;
+
+                let mut day = first;
+                loop {
+                    days.push(WEEKDAYS[day]);
+                    if day == last {
+                        break;
+                    }
+                    day = (day + 1) % 7;
+                }
+            }
+            None => days.push(WEEKDAYS[weekday(token)?]),
+        }
+    }
+
+    Some(days)
+}
+
+/// Parses a single time of operation field (see Section 5.195 of ARINC 424), such as `MON-FRI0800-1600` or `H24`
+fn map_operating_period(code: &str) -> Option<OperatingPeriod> {
+    let code = code.trim().to_uppercase();
+
+    if code.is_empty() {
+        return None;
+    }
+
+    if code.contains("H24") || code.contains("CONT") {
+        return Some(OperatingPeriod {
+            days: WEEKDAYS.to_vec(),
+            start: 0.,
+            end: 1440.,
+        });
+    }
+
+    let time_start = code.find(|c: char| c.is_ascii_digit())?;
+    let days = map_weekdays(&code[..time_start])?;
+
+    let digits = code[time_start..]
+        .chars()
+        .filter(|c| c.is_ascii_digit())
+        .collect::<String>();
+
+    let minutes = |hhmm: &str| -> Option<Minutes> {
+        let hours = hhmm.get(0..2)?.parse::<u32>().ok()?;
+        let minutes = hhmm.get(2..4)?.parse::<u32>().ok()?;
+
+        Some((hours * 60 + minutes) as Minutes)
+    };
+
+    Some(OperatingPeriod {
+        days,
+        start: minutes(digits.get(0..4)?)?,
+        end: minutes(digits.get(4..8)?)?,
+    })
+}
+
+/// Parses the time of operation fields of a communication into a schedule, returning `None` if none are set
+fn map_operating_schedule(times: &[&Option<String>]) -> Option<OperatingSchedule> {
+    let periods = times
+        .iter()
+        .filter_map(|time| time.as_deref().and_then(map_operating_period))
+        .collect::<Vec<_>>();
+
+    if periods.is_empty() {
+        None
+    } else {
+        Some(OperatingSchedule { periods })
+    }
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Debug, Clone, Copy)]
+/// The sector of airspace which a communication serves, parsed from its sectorization
+pub struct CommunicationSector {
+    /// The magnetic bearing from the sector facility at which this sector starts, going clockwise
+    pub bearing_from: Degrees,
+    /// The magnetic bearing from the sector facility at which this sector ends
+    pub bearing_to: Degrees,
+    /// The lowest altitude of this sector in feet, if limited
+    pub lower_altitude: Option<Feet>,
+    /// The highest altitude of this sector in feet, if limited
+    pub upper_altitude: Option<Feet>,
+}
+
+impl CommunicationSector {
+    /// Checks whether or not a magnetic bearing from the sector facility and, if known, an altitude is within this
+    /// sector
+    pub fn contains(&self, bearing: Degrees, altitude: Option<Feet>) -> bool {
+        let bearing = bearing.rem_euclid(360.0);
+
+        let in_bearings = if self.bearing_from == self.bearing_to {
+            true
+        } else if self.bearing_from < self.bearing_to {
+            bearing >= self.bearing_from && bearing <= self.bearing_to
+        } else {
+            bearing >= self.bearing_from || bearing <= self.bearing_to
+        };
+
+        let in_altitudes = altitude.is_none_or(|altitude| {
+            self.lower_altitude.is_none_or(|lower| altitude >= lower)
+                && self.upper_altitude.is_none_or(|upper| altitude <= upper)
+        });
+
+        in_bearings && in_altitudes
+    }
+}
+
+/// Parses a sectorization such as `090270`, `270090 BLW5000` or `000180 3000-10000`
+fn map_sectorization(code: &str) -> Option<CommunicationSector> {
+    let captures = SECTORIZATION_REGEX.captures(code.trim())?;
+
+    let number = |index: usize| {
+        captures
+            .get(index)
+            .and_then(|m| m.as_str().parse::<f64>().ok())
+    };
+
+    let (lower_altitude, upper_altitude) = match captures.get(3).map(|m| m.as_str()) {
+        Some("ABV") => (number(4), None),
+        Some("BLW") => (None, number(4)),
+        _ => (number(5), number(6)),
+    };
+
+    Some(CommunicationSector {
+        bearing_from: number(1)?,
+        bearing_to: number(2)?,
+        lower_altitude,
+        upper_altitude,
+    })
+}
+
 #[serde_with::skip_serializing_none]
 #[derive(Serialize, Debug, Default)]
 /// Represents a communication station at an airport or in an enroute fir
@@ -37,6 +243,16 @@ pub struct Communication {
     pub sector_facility_icao_code: Option<String>, // new
     /// Bearings from the sector facility is applicable to the communication
     pub sectorization: Option<String>, // new
+    /// The decoded form of `sectorization`
+    pub sector: Option<CommunicationSector>,
+    /// The times in which this communication is operating, if published
+    pub schedule: Option<OperatingSchedule>,
+    /// Whether this communication transmits only, receives only or both, for communications with a guard frequency
+    pub guard_transmit: Option<String>,
+    /// Additional information about this communication
+    pub narrative: Option<String>,
+    /// The services offered by this communication
+    pub service_indicator: Option<String>,
 }
 
 impl From<sql::AirportCommunication> for Communication {
@@ -58,7 +274,20 @@ impl From<sql::AirportCommunication> for Communication {
             remote_facility_icao_code: row.remote_facility_icao_code,
             sector_facility: row.sector_facility,
             sector_facility_icao_code: row.sector_facility_icao_code,
+            sector: row.sectorization.as_deref().and_then(map_sectorization),
             sectorization: row.sectorization,
+            schedule: map_operating_schedule(&[
+                &row.time_of_operation_1,
+                &row.time_of_operation_2,
+                &row.time_of_operation_3,
+                &row.time_of_operation_4,
+                &row.time_of_operation_5,
+                &row.time_of_operation_6,
+                &row.time_of_operation_7,
+            ]),
+            guard_transmit: row.guard_transmit,
+            narrative: row.narritive,
+            service_indicator: row.service_indicator,
         }
     }
 }
@@ -80,7 +309,139 @@ impl From<sql::EnrouteCommunication> for Communication {
             },
             remote_facility: row.remote_facility,
             remote_facility_icao_code: row.remote_facility_icao_code,
+            service_indicator: row.service_indicator,
             ..Default::default()
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Monday 2024-01-01 00:00 UTC
+    const MONDAY: u64 = 1_704_067_200;
+
+    fn time(day: u64, hours: u64, minutes: u64) -> u64 {
+        MONDAY + day * 86400 + hours * 3600 + minutes * 60
+    }
+
+    #[test]
+    fn parses_weekdays() {
+        assert_eq!(map_weekdays("DLY"), Some(WEEKDAYS.to_vec()));
+        assert_eq!(map_weekdays(""), Some(WEEKDAYS.to_vec()));
+        assert_eq!(
+            map_weekdays("MON-WED"),
+            Some(vec![Weekday::Monday, Weekday::Tuesday, Weekday::Wednesday])
+        );
+        assert_eq!(
+            map_weekdays("SAT,SUN"),
+            Some(vec![Weekday::Saturday, Weekday::Sunday])
+        );
+        assert_eq!(
+            map_weekdays("FRI-MON"),
+            Some(vec![
+                Weekday::Friday,
+                Weekday::Saturday,
+                Weekday::Sunday,
+                Weekday::Monday
+            ])
+        );
+        assert_eq!(map_weekdays("XYZ"), None);
+    }
+
+    #[test]
+    fn parses_operating_periods() {
+        let period = map_operating_period("MON-FRI0800-1630").unwrap();
+        assert_eq!(period.days, WEEKDAYS[0..5].to_vec());
+        assert_eq!(period.start, 480.);
+        assert_eq!(period.end, 990.);
+
+        let continuous = map_operating_period("H24").unwrap();
+        assert_eq!(continuous.days, WEEKDAYS.to_vec());
+        assert_eq!((continuous.start, continuous.end), (0., 1440.));
+
+        let daily = map_operating_period("2200-0600").unwrap();
+        assert_eq!(daily.days, WEEKDAYS.to_vec());
+        assert_eq!((daily.start, daily.end), (1320., 360.));
+
+        assert!(map_operating_period("").is_none());
+        assert!(map_operating_period("MON-FRI08").is_none());
+        assert!(map_operating_period("SEE NOTAM").is_none());
+    }
+
+    #[test]
+    fn schedule_is_active_within_periods() {
+        let schedule =
+            map_operating_schedule(&[&Some("MON-FRI0800-1600".to_string()), &None]).unwrap();
+
+        assert!(schedule.is_active(time(0, 8, 0)));
+        assert!(schedule.is_active(time(4, 15, 59)));
+        assert!(!schedule.is_active(time(0, 16, 0)));
+        assert!(!schedule.is_active(time(0, 7, 59)));
+        assert!(!schedule.is_active(time(5, 12, 0)));
+    }
+
+    #[test]
+    fn schedule_is_active_past_midnight() {
+        let schedule = map_operating_schedule(&[&Some("FRI2200-0600".to_string())]).unwrap();
+
+        assert!(schedule.is_active(time(4, 23, 0)));
+        assert!(schedule.is_active(time(5, 5, 59)));
+        assert!(!schedule.is_active(time(5, 6, 0)));
+        assert!(!schedule.is_active(time(5, 23, 0)));
+        assert!(!schedule.is_active(time(4, 5, 0)));
+    }
+
+    #[test]
+    fn schedule_requires_a_period() {
+        assert!(map_operating_schedule(&[&None, &Some(" ".to_string())]).is_none());
+    }
+
+    #[test]
+    fn parses_sectorization() {
+        let sector = map_sectorization("090270").unwrap();
+        assert_eq!((sector.bearing_from, sector.bearing_to), (90., 270.));
+        assert_eq!((sector.lower_altitude, sector.upper_altitude), (None, None));
+
+        let below = map_sectorization("270090 BLW5000").unwrap();
+        assert_eq!(
+            (below.lower_altitude, below.upper_altitude),
+            (None, Some(5000.))
+        );
+
+        let above = map_sectorization("270090ABV 5000").unwrap();
+        assert_eq!(
+            (above.lower_altitude, above.upper_altitude),
+            (Some(5000.), None)
+        );
+
+        let between = map_sectorization(" 000180 3000-10000").unwrap();
+        assert_eq!(
+            (between.lower_altitude, between.upper_altitude),
+            (Some(3000.), Some(10000.))
+        );
+
+        assert!(map_sectorization("ALL").is_none());
+        assert!(map_sectorization("0901").is_none());
+    }
+
+    #[test]
+    fn sector_contains_bearings_and_altitudes() {
+        let sector = map_sectorization("090270 BLW5000").unwrap();
+        assert!(sector.contains(180., None));
+        assert!(sector.contains(90., Some(5000.)));
+        assert!(!sector.contains(180., Some(6000.)));
+        assert!(!sector.contains(0., None));
+
+        let wrapping = map_sectorization("270090").unwrap();
+        assert!(wrapping.contains(0., None));
+        assert!(wrapping.contains(-10., None));
+        assert!(wrapping.contains(360., None));
+        assert!(!wrapping.contains(180., None));
+
+        let full = map_sectorization("000000 3000-10000").unwrap();
+        assert!(full.contains(123., Some(3000.)));
+        assert!(!full.contains(123., Some(2999.)));
+    }
+}
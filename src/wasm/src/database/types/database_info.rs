@@ -19,12 +19,19 @@ pub struct DatabaseInfo {
 /// Converts a string of the format `DDMMDDMMYY` into a tuple of two strings of the format `DD-MM-YYYY`.
 ///
 /// If the previous month is greater than the current month, the previous year is decremented by 1.
-fn parse_from_to(data: String) -> Result<(String, String), <u32 as FromStr>::Err> {
-    let from_day = data[0..2].parse::<u32>()?;
-    let from_month = data[2..4].parse::<u32>()?;
-    let to_day = data[4..6].parse::<u32>()?;
-    let to_month = data[6..8].parse::<u32>()?;
-    let to_year = data[8..10].parse::<u32>()?;
+pub fn parse_from_to(data: String) -> Result<(String, String), <u32 as FromStr>::Err> {
+    // A field which is missing from a malformed string fails to parse as empty, rather than panicking
+    let field = |start: usize| {
+        data.get(start..start + 2)
+            .unwrap_or_default()
+            .parse::<u32>()
+    };
+
+    let from_day = field(0)?;
+    let from_month = field(2)?;
+    let to_day = field(4)?;
+    let to_month = field(6)?;
+    let to_year = field(8)?;
 
     let from_year = if to_month < from_month {
         to_year - 1
@@ -47,3 +54,32 @@ impl From<sql::Header> for DatabaseInfo {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_date_ranges() {
+        assert_eq!(
+            parse_from_to("0101280124".to_string()),
+            Ok(("01-01-2024".to_string(), "28-01-2024".to_string()))
+        );
+    }
+
+    #[test]
+    fn parses_date_ranges_across_new_year() {
+        assert_eq!(
+            parse_from_to("2812250124".to_string()),
+            Ok(("28-12-2023".to_string(), "25-01-2024".to_string()))
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_date_ranges() {
+        assert!(parse_from_to(String::new()).is_err());
+        assert!(parse_from_to("010128".to_string()).is_err());
+        assert!(parse_from_to("01AB280124".to_string()).is_err());
+        assert!(parse_from_to("0101280\u{e9}4".to_string()).is_err());
+    }
+}
@@ -374,7 +374,7 @@ pub enum RunwayLights {
     No,
 }
 
-#[derive(Serialize, Deserialize, Debug, Copy, Clone)]
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
 pub enum RunwaySurface {
     #[serde(rename = "GRVL")]
     Gravel,
@@ -445,3 +445,132 @@ pub enum AuthorizationRequired {
     #[serde(rename = "N")]
     No,
 }
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub enum VhfNavaidType {
+    Vor,
+    VorDme,
+    Vortac,
+    Tacan,
+    Dme,
+    IlsDme,
+    IlsTacan,
+    MlsDme,
+    Unknown,
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub enum VhfNavaidCoverage {
+    #[serde(rename = "T")]
+    Terminal,
+    #[serde(rename = "L")]
+    LowAltitude,
+    #[serde(rename = "H")]
+    HighAltitude,
+    #[serde(rename = "U")]
+    Undefined,
+    /// Coverage class used for ILS/TACAN installations
+    #[serde(rename = "C")]
+    IlsTacan,
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub enum WaypointUsage {
+    #[serde(rename = "H")]
+    High,
+    #[serde(rename = "L")]
+    Low,
+    #[serde(rename = "B")]
+    Both,
+    /// Waypoint used only within terminal procedures
+    #[serde(rename = "T")]
+    Terminal,
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Default)]
+/// The reference a wind direction is given in
+pub enum WindReference {
+    /// Wind direction relative to true north, as reported in a METAR
+    #[default]
+    True,
+    /// Wind direction relative to magnetic north, as broadcast on the ATIS
+    Magnetic,
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub enum Weekday {
+    #[serde(rename = "MON")]
+    Monday,
+    #[serde(rename = "TUE")]
+    Tuesday,
+    #[serde(rename = "WED")]
+    Wednesday,
+    #[serde(rename = "THU")]
+    Thursday,
+    #[serde(rename = "FRI")]
+    Friday,
+    #[serde(rename = "SAT")]
+    Saturday,
+    #[serde(rename = "SUN")]
+    Sunday,
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+/// The sections which can be included in the details of an airport
+pub enum AirportDetailsInclude {
+    #[serde(rename = "runways")]
+    Runways,
+    #[serde(rename = "communications")]
+    Communications,
+    #[serde(rename = "gates")]
+    Gates,
+    #[serde(rename = "gls_navaids")]
+    GlsNavaids,
+    #[serde(rename = "path_points")]
+    PathPoints,
+    #[serde(rename = "departures")]
+    Departures,
+    #[serde(rename = "arrivals")]
+    Arrivals,
+    #[serde(rename = "approaches")]
+    Approaches,
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+/// The approach category of an aircraft, based on its speed at the threshold
+pub enum AircraftCategory {
+    #[serde(rename = "A")]
+    A,
+    #[serde(rename = "B")]
+    B,
+    #[serde(rename = "C")]
+    C,
+    #[serde(rename = "D")]
+    D,
+    #[serde(rename = "E")]
+    E,
+    #[serde(rename = "H")]
+    Helicopter,
+}
+
+impl AircraftCategory {
+    /// The character used for this category in the `aircraft_category` column of the database
+    pub fn code(self) -> char {
+        match self {
+            Self::A => 'A',
+            Self::B => 'B',
+            Self::C => 'C',
+            Self::D => 'D',
+            Self::E => 'E',
+            Self::Helicopter => 'H',
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+/// The kind of a procedure at an airport
+pub enum ProcedureKind {
+    Departure,
+    Arrival,
+    Approach,
+}
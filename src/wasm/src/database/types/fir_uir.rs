@@ -0,0 +1,365 @@
+use crate::database::utils::{Coordinates, NauticalMiles};
+
+use super::sql;
+
+/// The lateral boundary of an FIR or UIR, used to work out which FIR a position is in
+pub struct FirUirBoundary {
+    /// The identifier of the FIR or UIR, such as `EGTT` or `KZNY`
+    pub ident: String,
+    /// Whether the boundary is of an FIR (`F`), a UIR (`U`) or both (`B`)
+    pub indicator: Option<String>,
+    /// The vertices of the boundary in order. Arcs along the boundary are approximated by the straight line between
+    /// their end points
+    pub points: Vec<Coordinates>,
+    /// The center and radius of the boundary, if it is a circle
+    pub circle: Option<(Coordinates, NauticalMiles)>,
+}
+
+impl FirUirBoundary {
+    /// Returns the Southwest and Northeast corner of a box containing this boundary
+    pub fn bounds(&self) -> (Coordinates, Coordinates) {
+        if let Some((center, radius)) = &self.circle {
+            let (mut south_west, mut north_east) = center.distance_bounds(*radius);
+
+            // Circles crossing the antimeridian are widened to cover every longitude
+            if south_west.long > north_east.long {
+                south_west.long = -180.0;
+                north_east.long = 180.0;
+            }
+
+            return (south_west, north_east);
+        }
+
+        self.points.iter().fold(
+            (
+                Coordinates {
+                    lat: f64::MAX,
+                    long: f64::MAX,
+                },
+                Coordinates {
+                    lat: f64::MIN,
+                    long: f64::MIN,
+                },
+            ),
+            |(south_west, north_east), point| {
+                (
+                    Coordinates {
+                        lat: south_west.lat.min(point.lat),
+                        long: south_west.long.min(point.long),
+                    },
+                    Coordinates {
+                        lat: north_east.lat.max(point.lat),
+                        long: north_east.long.max(point.long),
+                    },
+                )
+            },
+        )
+    }
+
+    /// Checks whether or not a position is within this boundary
+    pub fn contains(&self, position: &Coordinates) -> bool {
+        if let Some((center, radius)) = &self.circle {
+            return center.distance_to(position) <= *radius;
+        }
+
+        let Some(first) = self.points.first() else {
+            return false;
+        };
+
+        // Unwrap longitudes around the first point so boundaries crossing the antimeridian stay continuous
+        let unwrap =
+            |long: f64, reference: f64| long + 360.0 * ((reference - long) / 360.0).round();
+
+        let mut vertices = Vec::with_capacity(self.points.len());
+        let mut previous = first.long;
+        for point in &self.points {
+            let long = unwrap(point.long, previous);
+            vertices.push((long, point.lat));
+            previous = long;
+        }
+
+        let long = unwrap(position.long, first.long);
+
+        [long - 360.0, long, long + 360.0].iter().any(|&long| {
+            let mut inside = false;
+            let mut j = vertices.len() - 1;
+
+            for i in 0..vertices.len() {
+                let (long_i, lat_i) = vertices[i];
+                let (long_j, lat_j) = vertices[j];
+
+                if (lat_i > position.lat) != (lat_j > position.lat)
+                    && long < (long_j - long_i) * (position.lat - lat_i) / (lat_j - lat_i) + long_i
+                {
+                    inside = !inside;
+                }
+
+                j = i;
+            }
+
+            inside
+        })
+    }
+}
+
+/// The box containing the boundary of an FIR or UIR, used to find the boundaries which may contain a position
+pub struct FirUirBounds {
+    /// The identifier of the FIR or UIR, such as `EGTT` or `KZNY`
+    pub ident: String,
+    /// Whether the boundary is of an FIR (`F`), a UIR (`U`) or both (`B`)
+    pub indicator: Option<String>,
+    /// The Southwest corner of the box
+    pub south_west: Coordinates,
+    /// The Northeast corner of the box
+    pub north_east: Coordinates,
+}
+
+impl FirUirBounds {
+    /// Checks whether or not a position is within this box. Boundaries which cross the antimeridian have a box
+    /// spanning more than half of the globe, so they contain every longitude
+    pub fn contains(&self, position: &Coordinates) -> bool {
+        position.lat >= self.south_west.lat
+            && position.lat <= self.north_east.lat
+            && (self.north_east.long - self.south_west.long > 180.0
+                || (position.long >= self.south_west.long && position.long <= self.north_east.long))
+    }
+}
+
+impl From<&FirUirBoundary> for FirUirBounds {
+    fn from(boundary: &FirUirBoundary) -> Self {
+        let (south_west, north_east) = boundary.bounds();
+
+        Self {
+            ident: boundary.ident.clone(),
+            indicator: boundary.indicator.clone(),
+            south_west,
+            north_east,
+        }
+    }
+}
+
+/// Groups FIR/UIR boundary rows, ordered by identifier, indicator and sequence number, into boundaries. An FIR and a
+/// UIR can share an identifier, so each pair of identifier and indicator is its own boundary
+pub fn map_fir_uir_boundaries(data: Vec<sql::FirUir>) -> Vec<FirUirBoundary> {
+    data.into_iter()
+        .fold(Vec::new(), |mut boundaries: Vec<FirUirBoundary>, row| {
+            let Some(ident) = row.fir_uir_identifier else {
+                return boundaries;
+            };
+
+            if boundaries.last().is_none_or(|boundary| {
+                boundary.ident != ident || boundary.indicator != row.fir_uir_indicator
+            }) {
+                boundaries.push(FirUirBoundary {
+                    ident,
+                    indicator: row.fir_uir_indicator,
+                    points: Vec::new(),
+                    circle: None,
+                });
+            }
+
+            let boundary = boundaries.last_mut().unwrap();
+
+            if row
+                .boundary_via
+                .as_deref()
+                .is_some_and(|via| via.starts_with('C'))
+            {
+                boundary.circle = row
+                    .arc_origin_latitude
+                    .zip(row.arc_origin_longitude)
+                    .map(|(lat, long)| Coordinates { lat, long })
+                    .zip(row.arc_distance);
+            } else if let Some((lat, long)) = row.fir_uir_latitude.zip(row.fir_uir_longitude) {
+                boundary.points.push(Coordinates { lat, long });
+            }
+
+            boundaries
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(lat: f64, long: f64) -> Coordinates {
+        Coordinates { lat, long }
+    }
+
+    fn polygon(points: &[(f64, f64)]) -> FirUirBoundary {
+        FirUirBoundary {
+            ident: "TEST".to_string(),
+            indicator: Some("F".to_string()),
+            points: points.iter().map(|&(lat, long)| point(lat, long)).collect(),
+            circle: None,
+        }
+    }
+
+    fn row(
+        ident: &str,
+        indicator: &str,
+        via: &str,
+        location: Option<(f64, f64)>,
+        arc: Option<(f64, f64, f64)>,
+    ) -> sql::FirUir {
+        sql::FirUir {
+            area_code: None,
+            fir_uir_identifier: Some(ident.to_string()),
+            fir_uir_address: None,
+            fir_uir_name: None,
+            fir_uir_indicator: Some(indicator.to_string()),
+            seqno: None,
+            boundary_via: Some(via.to_string()),
+            adjacent_fir_identifier: None,
+            adjacent_uir_identifier: None,
+            reporting_units_speed: None,
+            reporting_units_altitude: None,
+            fir_uir_latitude: location.map(|(lat, _)| lat),
+            fir_uir_longitude: location.map(|(_, long)| long),
+            arc_origin_latitude: arc.map(|(lat, _, _)| lat),
+            arc_origin_longitude: arc.map(|(_, long, _)| long),
+            arc_distance: arc.map(|(_, _, distance)| distance),
+            arc_bearing: None,
+            fir_upper_limit: None,
+            uir_lower_limit: None,
+            uir_upper_limit: None,
+            cruise_table_identifier: None,
+        }
+    }
+
+    #[test]
+    fn polygon_contains_points_inside() {
+        let square = polygon(&[(50.0, 0.0), (50.0, 10.0), (40.0, 10.0), (40.0, 0.0)]);
+
+        assert!(square.contains(&point(45.0, 5.0)));
+        assert!(!square.contains(&point(55.0, 5.0)));
+        assert!(!square.contains(&point(45.0, -5.0)));
+        assert!(!square.contains(&point(45.0, 15.0)));
+    }
+
+    #[test]
+    fn concave_polygon_excludes_its_notch() {
+        let l_shape = polygon(&[
+            (50.0, 0.0),
+            (50.0, 5.0),
+            (45.0, 5.0),
+            (45.0, 10.0),
+            (40.0, 10.0),
+            (40.0, 0.0),
+        ]);
+
+        assert!(l_shape.contains(&point(47.0, 2.0)));
+        assert!(l_shape.contains(&point(42.0, 8.0)));
+        assert!(!l_shape.contains(&point(47.0, 8.0)));
+    }
+
+    #[test]
+    fn polygon_crossing_the_antimeridian() {
+        let pacific = polygon(&[
+            (10.0, 170.0),
+            (10.0, -170.0),
+            (-10.0, -170.0),
+            (-10.0, 170.0),
+        ]);
+
+        assert!(pacific.contains(&point(0.0, 179.0)));
+        assert!(pacific.contains(&point(0.0, -179.0)));
+        assert!(pacific.contains(&point(0.0, 180.0)));
+        assert!(!pacific.contains(&point(0.0, 160.0)));
+        assert!(!pacific.contains(&point(0.0, -160.0)));
+        assert!(!pacific.contains(&point(0.0, 0.0)));
+    }
+
+    #[test]
+    fn circle_contains_points_within_radius() {
+        let center = point(45.0, 5.0);
+        let circle = FirUirBoundary {
+            ident: "TEST".to_string(),
+            indicator: Some("F".to_string()),
+            points: Vec::new(),
+            circle: Some((center, 50.0)),
+        };
+
+        assert!(circle.contains(&center.destination(123.0, 49.0)));
+        assert!(!circle.contains(&center.destination(123.0, 51.0)));
+    }
+
+    #[test]
+    fn empty_boundary_contains_nothing() {
+        assert!(!polygon(&[]).contains(&point(0.0, 0.0)));
+    }
+
+    #[test]
+    fn bounds_of_polygons_and_circles() {
+        let (south_west, north_east) = polygon(&[(50.0, 0.0), (52.0, 10.0), (40.0, 3.0)]).bounds();
+        assert_eq!((south_west.lat, south_west.long), (40.0, 0.0));
+        assert_eq!((north_east.lat, north_east.long), (52.0, 10.0));
+
+        let antimeridian_circle = FirUirBoundary {
+            ident: "TEST".to_string(),
+            indicator: Some("F".to_string()),
+            points: Vec::new(),
+            circle: Some((point(0.0, 179.5), 60.0)),
+        };
+        let (south_west, north_east) = antimeridian_circle.bounds();
+        assert_eq!((south_west.long, north_east.long), (-180.0, 180.0));
+    }
+
+    #[test]
+    fn bounding_boxes_contain_points_inside() {
+        let bounds = FirUirBounds {
+            ident: "TEST".to_string(),
+            indicator: Some("F".to_string()),
+            south_west: point(40.0, 0.0),
+            north_east: point(50.0, 10.0),
+        };
+
+        assert!(bounds.contains(&point(45.0, 5.0)));
+        assert!(bounds.contains(&point(40.0, 10.0)));
+        assert!(!bounds.contains(&point(51.0, 5.0)));
+        assert!(!bounds.contains(&point(45.0, 11.0)));
+
+        let antimeridian = FirUirBounds {
+            south_west: point(-10.0, -170.0),
+            north_east: point(10.0, 170.0),
+            ..bounds
+        };
+        assert!(antimeridian.contains(&point(0.0, 179.0)));
+        assert!(!antimeridian.contains(&point(20.0, 179.0)));
+    }
+
+    #[test]
+    fn groups_rows_into_boundaries() {
+        let boundaries = map_fir_uir_boundaries(vec![
+            row("AAAA", "F", "G", Some((50.0, 0.0)), None),
+            row("AAAA", "F", "G", Some((50.0, 10.0)), None),
+            row("AAAA", "F", "GE", Some((40.0, 10.0)), None),
+            row("AAAA", "U", "G", Some((60.0, 0.0)), None),
+            row("AAAA", "U", "GE", Some((30.0, 10.0)), None),
+            row("BBBB", "F", "CE", None, Some((45.0, 5.0, 30.0))),
+        ]);
+
+        assert_eq!(boundaries.len(), 3);
+        assert_eq!(boundaries[0].ident, "AAAA");
+        assert_eq!(boundaries[0].indicator.as_deref(), Some("F"));
+        assert_eq!(boundaries[0].points.len(), 3);
+        assert!(boundaries[0].circle.is_none());
+        assert_eq!(boundaries[1].ident, "AAAA");
+        assert_eq!(boundaries[1].indicator.as_deref(), Some("U"));
+        assert_eq!(boundaries[1].points.len(), 2);
+        assert_eq!(boundaries[2].ident, "BBBB");
+        assert!(boundaries[2].points.is_empty());
+        assert_eq!(boundaries[2].circle.map(|(_, radius)| radius), Some(30.0));
+    }
+
+    #[test]
+    fn bounds_of_circle_boundaries_come_from_the_arc_origin() {
+        let boundaries =
+            map_fir_uir_boundaries(vec![row("BBBB", "F", "CE", None, Some((45.0, 5.0, 60.0)))]);
+        let bounds = FirUirBounds::from(&boundaries[0]);
+
+        assert!(bounds.contains(&point(45.0, 5.0)));
+        assert!(bounds.contains(&point(45.9, 5.0)));
+        assert!(!bounds.contains(&point(46.1, 5.0)));
+    }
+}
@@ -0,0 +1,67 @@
+//! Rows of the navigation data tables which are shared between the unit tests
+
+use serde_json::{json, Value};
+
+use super::sql;
+use crate::database::utils::{Coordinates, Degrees};
+
+/// Adds the fields of the JSON object `extra` to the JSON object `fields`, replacing any which are already present
+pub fn with(mut fields: Value, extra: Value) -> Value {
+    fields
+        .as_object_mut()
+        .unwrap()
+        .extend(extra.as_object().unwrap().clone());
+
+    fields
+}
+
+/// A runway threshold at the airport `KXYZ`, 6076 feet long and 150 feet wide
+pub fn runway(ident: &str, location: Coordinates, true_bearing: Degrees) -> sql::Runways {
+    sql::Runways {
+        airport_identifier: "KXYZ".to_string(),
+        area_code: Some("USA".to_string()),
+        displaced_threshold_distance: None,
+        icao_code: Some("K1".to_string()),
+        landing_threshold_elevation: 100.0,
+        llz_identifier: None,
+        llz_mls_gls_category: None,
+        part_time_lights: None,
+        runway_gradient: None,
+        runway_identifier: ident.to_string(),
+        runway_latitude: Some(location.lat),
+        runway_length: 6076.0,
+        runway_lights: None,
+        runway_longitude: Some(location.long),
+        runway_magnetic_bearing: Some(true_bearing),
+        runway_true_bearing: Some(true_bearing),
+        runway_width: 150.0,
+        surface_code: None,
+        threshold_crossing_height: None,
+        traffic_pattern: None,
+    }
+}
+
+/// A TF leg of the procedure `ABC1A` at the airport `KXYZ`, with `fields` replacing or adding to its columns
+pub fn procedure_leg(fields: Value) -> sql::Procedures {
+    let row = json!({
+        "airport_identifier": "KXYZ",
+        "area_code": "USA",
+        "path_termination": "TF",
+        "procedure_identifier": "ABC1A",
+        "route_type": "2",
+        "seqno": 10.0,
+    });
+
+    serde_json::from_value(with(row, fields)).unwrap()
+}
+
+/// The columns of a procedure leg which end it at the fix `ident` from the table `ref_table`
+pub fn fix(ident: &str, ref_table: &str, location: Coordinates) -> Value {
+    json!({
+        "waypoint_identifier": ident,
+        "waypoint_icao_code": "K1",
+        "waypoint_latitude": location.lat,
+        "waypoint_longitude": location.long,
+        "waypoint_ref_table": ref_table,
+    })
+}
@@ -0,0 +1,95 @@
+use sentry::capture_message;
+use serde::Serialize;
+
+use crate::database::utils::{Coordinates, Degrees, Feet, MegaHertz};
+
+use super::sql;
+
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Clone)]
+/// Represents the localizer and, if it has one, the glideslope of an ILS, MLS or IGS installation
+pub struct IlsNavaid {
+    /// The Geographic region where this navaid is
+    pub area_code: Option<String>,
+    /// The identifier of the airport which this navaid serves
+    pub airport_ident: String,
+    /// The icao prefix of the region this navaid is in
+    pub icao_code: String,
+    /// The identifier of this navaid, such as `IKEN` or `ILL`
+    pub ident: String,
+    /// The identifier of the runway this navaid serves, if any
+    pub runway_ident: Option<String>,
+    /// The frequency of this navaid in megahertz
+    pub frequency: MegaHertz,
+    /// The geographic location of the localizer antenna of this navaid
+    pub location: Coordinates,
+    /// The magnetic bearing of the localizer course of this navaid
+    pub bearing: Option<Degrees>,
+    /// The width of the localizer course of this navaid in degrees
+    pub width: Option<Degrees>,
+    /// The category of this navaid, such as `1` or `3`
+    pub category: Option<String>,
+    /// The geographic location of the glideslope antenna of this navaid, if it has a glideslope
+    pub glideslope_location: Option<Coordinates>,
+    /// The angle of the glideslope of this navaid in degrees
+    pub glideslope_angle: Option<Degrees>,
+    /// The elevation of the glideslope antenna of this navaid in feet
+    pub glideslope_elevation: Option<Feet>,
+    /// The magnetic declination of this navaid in degrees
+    pub station_declination: Option<Degrees>,
+}
+
+impl From<sql::LocalizersGlideslopes> for IlsNavaid {
+    fn from(navaid: sql::LocalizersGlideslopes) -> Self {
+        let mut error_in_row = false;
+
+        let navaid_new = Self {
+            area_code: navaid.area_code,
+            airport_ident: navaid.airport_identifier.clone(),
+            icao_code: navaid.icao_code.unwrap_or_else(|| {
+                error_in_row = true;
+                "UNKN".to_string()
+            }),
+            ident: navaid.llz_identifier.clone(),
+            runway_ident: navaid.runway_identifier,
+            frequency: navaid.llz_frequency.unwrap_or_else(|| {
+                error_in_row = true;
+                0.
+            }),
+            location: Coordinates {
+                lat: navaid.llz_latitude.unwrap_or_else(|| {
+                    error_in_row = true;
+                    0.
+                }),
+                long: navaid.llz_longitude.unwrap_or_else(|| {
+                    error_in_row = true;
+                    0.
+                }),
+            },
+            bearing: navaid.llz_bearing,
+            width: navaid.llz_width,
+            category: navaid.ils_mls_gls_category,
+            glideslope_location: navaid
+                .gs_latitude
+                .zip(navaid.gs_longitude)
+                .map(|(lat, long)| Coordinates { lat, long }),
+            glideslope_angle: navaid.gs_angle,
+            glideslope_elevation: navaid.gs_elevation,
+            station_declination: navaid.station_declination,
+        };
+
+        if error_in_row {
+            let error_text = format!(
+                "Error found in IlsNavaid: {}",
+                serde_json::to_string(&navaid_new).unwrap_or(format!(
+                    "Error serializing output, {} navaid {}",
+                    navaid.airport_identifier, navaid.llz_identifier
+                ))
+            );
+
+            capture_message(&error_text, sentry::Level::Warning);
+        }
+
+        navaid_new
+    }
+}
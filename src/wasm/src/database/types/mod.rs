@@ -4,14 +4,20 @@ pub mod airway;
 pub mod communication;
 pub mod database_info;
 pub mod enums;
+pub mod fir_uir;
 pub mod fix;
+#[cfg(test)]
+pub mod fixtures;
 pub mod gate;
 pub mod gls_navaid;
+pub mod ils_navaid;
 pub mod ndb_navaid;
 pub mod path_point;
 pub mod procedure;
 pub mod procedure_leg;
+pub mod radio_navaid;
 pub mod runway;
 pub mod sql;
+pub mod vertical_profile;
 pub mod vhf_navaid;
 pub mod waypoint;
@@ -6,7 +6,7 @@ use crate::database::utils::{Coordinates, KiloHertz, NauticalMiles};
 use super::sql;
 
 #[serde_with::skip_serializing_none]
-#[derive(Serialize)]
+#[derive(Serialize, Clone)]
 pub struct NdbNavaid {
     /// Represents the geographic region in which this NdbNavaid is located
     pub area_code: String,
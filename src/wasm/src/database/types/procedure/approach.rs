@@ -3,9 +3,20 @@ use std::collections::{hash_map::Entry, HashMap};
 use regex::Regex;
 use serde::Serialize;
 
-use crate::database::types::{enums::ApproachType, procedure_leg::ProcedureLeg, sql};
+use crate::database::{
+    types::{
+        enums::{ApproachType, ApproachTypeIdentifier, ProcedureTypeApproved},
+        fix::{Fix, FixType},
+        procedure_leg::{ProcedureLeg, ProcedureType},
+        sql,
+    },
+    utils::NauticalMiles,
+};
 
-use super::{apply_enroute_transition_leg, Transition};
+use super::{
+    apply_enroute_transition_leg, find_transition_legs, required_performance, ProcedureSummary,
+    SummaryRow, Transition,
+};
 
 #[derive(Serialize)]
 /// Represents an approach procedure for an airport.
@@ -43,6 +54,163 @@ pub struct Approach {
     ///
     /// This is not garunteed to match the type found through the `ident` field.
     approach_type: ApproachType,
+    /// A summary of the navigation capabilities which are required or available to fly this approach
+    capabilities: ApproachCapabilities,
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Default)]
+/// The navigation capabilities which are required or available to fly an `Approach`, summarised from its legs and path
+/// points
+pub struct ApproachCapabilities {
+    /// The smallest rnp (required navigational performance) of any leg in the approach (excluding the missed
+    /// approach) in nautical miles
+    rnp: Option<NauticalMiles>,
+    /// Whether any leg of the approach requires authorization (RNP AR)
+    authorization_required: bool,
+    /// Whether the approach can be flown to LNAV minima
+    lnav: bool,
+    /// Whether the approach can be flown to LNAV/VNAV minima
+    lnav_vnav: bool,
+    /// Whether the approach can be flown to LPV minima, based on its path point
+    lpv: bool,
+    /// Whether the approach can be flown to LP minima, based on its path point
+    lp: bool,
+    /// The ILS or GLS navaid which provides guidance for the final approach, if any
+    navaid: Option<Fix>,
+    /// The final approach fix (FAF) of the approach
+    final_approach_fix: Option<Fix>,
+    /// The missed approach point (MAP) of the approach
+    missed_approach_point: Option<Fix>,
+}
+
+impl ApproachCapabilities {
+    /// Summarises the capabilities of an approach from its legs and the path points at its airport
+    fn new(approach: &Approach, path_points: &[sql::Pathpoints]) -> Self {
+        let legs = || {
+            approach
+                .transitions
+                .iter()
+                .flat_map(|transition| transition.legs.iter())
+                .chain(approach.legs.iter())
+        };
+
+        let is_authorized = |procedure_type: &Option<ProcedureType>| {
+            procedure_type.as_ref().is_some_and(|procedure_type| {
+                matches!(procedure_type.authorized, Some(ProcedureTypeApproved::Yes))
+            })
+        };
+
+        // The fourth character of the description code marks the final approach fix and missed approach point
+        let find_fix = |code: char| {
+            approach
+                .legs
+                .iter()
+                .filter_map(|leg| leg.fix.as_ref())
+                .find(|fix| {
+                    fix.fix_code
+                        .as_ref()
+                        .and_then(|fix_code| fix_code.chars().nth(3))
+                        == Some(code)
+                })
+                .cloned()
+        };
+
+        let path_points = path_points
+            .iter()
+            .filter(|path_point| path_point.approach_procedure_ident == approach.ident);
+
+        let (rnp, authorization_required) =
+            required_performance(legs().map(|leg| (leg.rnp, leg.ar)));
+
+        Self {
+            rnp,
+            authorization_required,
+            lnav: legs().any(|leg| is_authorized(&leg.lnav_authorized)),
+            lnav_vnav: legs().any(|leg| is_authorized(&leg.lnav_vnav_authorized)),
+            lpv: path_points.clone().any(|path_point| {
+                matches!(
+                    path_point.approach_type_identifier,
+                    ApproachTypeIdentifier::LocalizerPerformanceVerticalGuidance
+                )
+            }),
+            lp: path_points.clone().any(|path_point| {
+                matches!(
+                    path_point.approach_type_identifier,
+                    ApproachTypeIdentifier::LocalizerPerformance
+                )
+            }),
+            navaid: approach
+                .legs
+                .iter()
+                .filter_map(|leg| leg.recommended_navaid.as_ref())
+                .find(|navaid| {
+                    matches!(
+                        navaid.fix_type,
+                        Some(FixType::IlsNavaid | FixType::GlsNavaid)
+                    )
+                })
+                .cloned(),
+            final_approach_fix: find_fix('F'),
+            missed_approach_point: find_fix('M'),
+        }
+    }
+}
+
+impl Approach {
+    /// Assembles the legs of this approach in the order they are flown: the transition and then the legs of the
+    /// approach, without the missed approach.
+    ///
+    /// The transition is left out if it is `None`. Returns `None` if the transition is not part of this approach.
+    pub fn assemble_legs(&self, transition: &Option<String>) -> Option<Vec<&ProcedureLeg>> {
+        let mut legs = Vec::new();
+
+        legs.extend(find_transition_legs(&self.transitions, transition)?);
+        legs.extend(&self.legs);
+
+        Some(legs)
+    }
+}
+
+/// Summarises the approaches at an airport from the header columns of their rows, without assembling their legs.
+///
+/// The rows of the missed approach are found in the same way as `map_approaches`, and are left out of the summary
+pub fn map_approach_summaries(data: Vec<sql::ProcedureHeaders>) -> Vec<ProcedureSummary> {
+    let mut procedure_ident = String::new();
+    let mut missed_started = false;
+
+    ProcedureSummary::map_summaries(data, &[], |row, summary| {
+        // The rows of each procedure are passed in together, so the state is reset on the first row of each
+        if row.procedure_identifier != procedure_ident {
+            procedure_ident = row.procedure_identifier.clone();
+            missed_started = false;
+            summary.runway_transitions = split_approach_ident(row.procedure_identifier.clone())
+                .map(|(_, runway_ident, _)| format!("RW{}", runway_ident))
+                .into_iter()
+                .collect();
+        }
+
+        if let Some(description_code) = &row.waypoint_description_code {
+            if description_code.chars().nth(2) == Some('M') {
+                missed_started = true;
+            }
+        }
+
+        match row.route_type.as_str() {
+            "A" => row
+                .transition_identifier
+                .clone()
+                .map_or(SummaryRow::Excluded, SummaryRow::EnrouteTransition),
+            "Z" => SummaryRow::Excluded,
+            _ if missed_started => SummaryRow::Excluded,
+            route_type => {
+                summary.approach_type =
+                    serde_json::from_value(serde_json::Value::String(route_type.to_string())).ok();
+
+                SummaryRow::Common(None)
+            }
+        }
+    })
 }
 
 /// Extracts the following information from a standard runway approach identifier.
@@ -76,11 +244,15 @@ pub fn split_approach_ident(ident: String) -> Option<(String, String, Option<Str
 ///
 /// This function requires complete data for a single airport and the same ordering as the database provides by default.
 ///
-/// The recommended SQL query to load the neccesary data for this function is:
+/// The recommended SQL queries to load the neccesary data for this function are:
 /// ```sql
 /// SELECT * FROM tbl_iaps WHERE airport_identifier = (?1)
+/// SELECT * FROM tbl_pp_pathpoint WHERE airport_identifier = (?1)
 /// ```
-pub fn map_approaches(data: Vec<sql::Procedures>) -> Vec<Approach> {
+pub fn map_approaches(
+    data: Vec<sql::Procedures>,
+    path_points: Vec<sql::Pathpoints>,
+) -> Vec<Approach> {
     let mut missed_started = false;
 
     data.into_iter()
@@ -101,6 +273,7 @@ pub fn map_approaches(data: Vec<sql::Procedures>) -> Vec<Approach> {
                                                            * find a row with a valid approach type (the first row in
                                                            * an approach will usually be a transition so it can not
                                                            * be used to find the approach type) */
+                        capabilities: ApproachCapabilities::default(),
                     })
                 }
             };
@@ -139,5 +312,183 @@ pub fn map_approaches(data: Vec<sql::Procedures>) -> Vec<Approach> {
             approaches
         })
         .into_values()
+        .map(|mut approach| {
+            approach.capabilities = ApproachCapabilities::new(&approach, &path_points);
+
+            approach
+        })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::database::{
+        types::fixtures::{self, with},
+        utils::Coordinates,
+    };
+
+    fn leg(procedure: &str, route_type: &str, fields: serde_json::Value) -> sql::Procedures {
+        fixtures::procedure_leg(with(
+            json!({ "procedure_identifier": procedure, "route_type": route_type }),
+            fields,
+        ))
+    }
+
+    fn fix(ident: &str, ref_table: &str, description_code: &str) -> serde_json::Value {
+        with(
+            fixtures::fix(
+                ident,
+                ref_table,
+                Coordinates {
+                    lat: 0.0,
+                    long: 0.0,
+                },
+            ),
+            json!({ "waypoint_description_code": description_code }),
+        )
+    }
+
+    fn path_point(procedure: &str, approach_type_identifier: &str) -> sql::Pathpoints {
+        serde_json::from_value(json!({
+            "airport_icao_code": "K1",
+            "airport_identifier": "KXYZ",
+            "approach_procedure_ident": procedure,
+            "approach_type_identifier": approach_type_identifier,
+            "area_code": "USA",
+            "course_width_at_threshold": 106.0,
+            "flight_path_alignment_point_latitude": 0.0,
+            "flight_path_alignment_point_longitude": 0.0,
+            "glide_path_angle": 3.0,
+            "gnss_channel_number": 40000.0,
+            "hal": 40.0,
+            "landing_threshold_point_latitude": 0.0,
+            "landing_threshold_point_longitude": 0.0,
+            "ltp_ellipsoid_height": 0.0,
+            "path_point_tch": 50.0,
+            "reference_path_identifier": "W27A",
+            "runway_identifier": "RW27L",
+            "sbas_service_provider_identifier": 0.0,
+            "tch_units_indicator": "F",
+            "val": 50.0,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn summarises_approach_capabilities() {
+        let approaches = map_approaches(
+            vec![
+                leg(
+                    "R27L",
+                    "A",
+                    with(
+                        fix("ABC", "EA", "E   "),
+                        json!({ "transition_identifier": "ABC", "rnp": 1.0 }),
+                    ),
+                ),
+                leg(
+                    "R27L",
+                    "R",
+                    with(
+                        fix("FAFWP", "PC", "E  F"),
+                        json!({ "rnp": 0.3, "lnav_authorized_sbas": "A" }),
+                    ),
+                ),
+                leg(
+                    "R27L",
+                    "R",
+                    with(fix("RW27L", "PG", "G  M"), json!({ "rnp": 0.3 })),
+                ),
+                leg(
+                    "R27L",
+                    "R",
+                    with(
+                        fix("MISSD", "PC", "E M "),
+                        json!({ "rnp": 0.1, "authorization_required": "Y" }),
+                    ),
+                ),
+            ],
+            vec![path_point("R27L", "LPV")],
+        );
+
+        let capabilities = &approaches[0].capabilities;
+
+        assert_eq!(approaches[0].missed_legs.len(), 1);
+        assert_eq!(capabilities.rnp, Some(0.3));
+        assert!(!capabilities.authorization_required);
+        assert!(capabilities.lnav);
+        assert!(!capabilities.lnav_vnav);
+        assert!(capabilities.lpv);
+        assert!(!capabilities.lp);
+        assert!(capabilities.navaid.is_none());
+        assert_eq!(
+            capabilities
+                .final_approach_fix
+                .as_ref()
+                .map(|fix| fix.ident.as_str()),
+            Some("FAFWP")
+        );
+        assert_eq!(
+            capabilities
+                .missed_approach_point
+                .as_ref()
+                .map(|fix| fix.ident.as_str()),
+            Some("RW27L")
+        );
+    }
+
+    #[test]
+    fn finds_the_final_approach_navaid() {
+        let navaid = json!({
+            "recommended_navaid": "IXYZ",
+            "recommended_navaid_icao_code": "K1",
+            "recommended_navaid_latitude": 0.0,
+            "recommended_navaid_longitude": 0.0,
+            "recommended_navaid_ref_table": "PI",
+        });
+
+        let approaches = map_approaches(
+            vec![
+                leg("I27L", "I", with(fix("FAFWP", "PC", "E  F"), navaid)),
+                leg(
+                    "I27L",
+                    "I",
+                    with(
+                        fix("RW27L", "PG", "G  M"),
+                        json!({ "authorization_required": "Y" }),
+                    ),
+                ),
+            ],
+            vec![path_point("R27L", "LPV")],
+        );
+
+        let capabilities = &approaches[0].capabilities;
+
+        assert!(capabilities.authorization_required);
+        assert!(capabilities.rnp.is_none());
+        assert!(!capabilities.lpv);
+        assert_eq!(
+            capabilities
+                .navaid
+                .as_ref()
+                .map(|navaid| navaid.ident.as_str()),
+            Some("IXYZ")
+        );
+    }
+
+    #[test]
+    fn splits_approach_idents() {
+        assert_eq!(
+            split_approach_ident("R27L".to_string()),
+            Some(("R".to_string(), "27L".to_string(), None))
+        );
+        assert_eq!(
+            split_approach_ident("I09-Y".to_string()),
+            Some(("I".to_string(), "09".to_string(), Some("Y".to_string())))
+        );
+        assert_eq!(split_approach_ident("RNVC".to_string()), None);
+    }
+}
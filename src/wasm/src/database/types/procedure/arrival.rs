@@ -5,7 +5,8 @@ use serde::Serialize;
 use crate::database::types::{procedure_leg::ProcedureLeg, sql};
 
 use super::{
-    apply_common_leg, apply_enroute_transition_leg, apply_runway_transition_leg, Transition,
+    apply_common_leg, apply_enroute_transition_leg, apply_runway_transition_leg,
+    find_transition_legs, ProcedureSummary, SummaryRow, Transition,
 };
 
 #[derive(Serialize)]
@@ -37,6 +38,46 @@ pub struct Arrival {
     runway_transitions: Vec<Transition>,
 }
 
+impl Arrival {
+    /// Assembles the legs of this arrival in the order they are flown: the enroute transition, the common legs and then the runway transition.
+    ///
+    /// Transitions which are `None` are left out. Returns `None` if a given transition is not part of this arrival.
+    pub fn assemble_legs(
+        &self,
+        runway_transition: &Option<String>,
+        enroute_transition: &Option<String>,
+    ) -> Option<Vec<&ProcedureLeg>> {
+        let mut legs = Vec::new();
+
+        legs.extend(find_transition_legs(
+            &self.enroute_transitions,
+            enroute_transition,
+        )?);
+        legs.extend(&self.common_legs);
+        legs.extend(find_transition_legs(
+            &self.runway_transitions,
+            runway_transition,
+        )?);
+
+        Some(legs)
+    }
+}
+
+/// Summarises the arrivals at an airport from the header columns of their rows, without assembling their legs
+pub fn map_arrival_summaries(
+    data: Vec<sql::ProcedureHeaders>,
+    runways: &[sql::Runways],
+) -> Vec<ProcedureSummary> {
+    ProcedureSummary::map_summaries(data, runways, |row, _| {
+        match (row.route_type.as_str(), row.transition_identifier.clone()) {
+            ("1" | "4" | "7" | "F", Some(ident)) => SummaryRow::EnrouteTransition(ident),
+            ("3" | "6" | "9" | "S", Some(ident)) => SummaryRow::RunwayTransition(ident),
+            ("2" | "5" | "8" | "M", ident) => SummaryRow::Common(ident),
+            _ => SummaryRow::Common(None),
+        }
+    })
+}
+
 /// Maps a list of arrival rows from the sqlite database into `Arrival` structs, by condensing them using
 /// `procedure_identifier` and `transition_identifier`
 ///
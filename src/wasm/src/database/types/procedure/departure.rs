@@ -5,7 +5,8 @@ use serde::Serialize;
 use crate::database::types::{procedure_leg::ProcedureLeg, sql};
 
 use super::{
-    apply_common_leg, apply_enroute_transition_leg, apply_runway_transition_leg, Transition,
+    apply_common_leg, apply_enroute_transition_leg, apply_runway_transition_leg,
+    find_transition_legs, ProcedureSummary, SummaryRow, Transition,
 };
 
 #[derive(Serialize)]
@@ -30,6 +31,46 @@ pub struct Departure {
     engine_out_legs: Vec<ProcedureLeg>,
 }
 
+impl Departure {
+    /// Assembles the legs of this departure in the order they are flown: the runway transition, the common legs and then the enroute transition.
+    ///
+    /// Transitions which are `None` are left out. Returns `None` if a given transition is not part of this departure.
+    pub fn assemble_legs(
+        &self,
+        runway_transition: &Option<String>,
+        enroute_transition: &Option<String>,
+    ) -> Option<Vec<&ProcedureLeg>> {
+        let mut legs = Vec::new();
+
+        legs.extend(find_transition_legs(
+            &self.runway_transitions,
+            runway_transition,
+        )?);
+        legs.extend(&self.common_legs);
+        legs.extend(find_transition_legs(
+            &self.enroute_transitions,
+            enroute_transition,
+        )?);
+
+        Some(legs)
+    }
+}
+
+/// Summarises the departures at an airport from the header columns of their rows, without assembling their legs
+pub fn map_departure_summaries(
+    data: Vec<sql::ProcedureHeaders>,
+    runways: &[sql::Runways],
+) -> Vec<ProcedureSummary> {
+    ProcedureSummary::map_summaries(data, runways, |row, _| {
+        match (row.route_type.as_str(), row.transition_identifier.clone()) {
+            ("1" | "4" | "F" | "T", Some(ident)) => SummaryRow::RunwayTransition(ident),
+            ("3" | "6" | "S" | "V", Some(ident)) => SummaryRow::EnrouteTransition(ident),
+            ("2" | "5" | "M", ident) => SummaryRow::Common(ident),
+            _ => SummaryRow::Common(None),
+        }
+    })
+}
+
 /// Maps a list of departure rows from the sqlite database into `Departure` structs, by condensing them using
 /// `procedure_identifier` and `transition_identifier`
 ///
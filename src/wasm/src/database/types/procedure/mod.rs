@@ -1,6 +1,14 @@
+use std::collections::HashMap;
+
 use serde::Serialize;
 
-use super::{procedure_leg::ProcedureLeg, sql::Runways};
+use crate::database::utils::NauticalMiles;
+
+use super::{
+    enums::{AircraftCategory, ApproachType, AuthorizationRequired},
+    procedure_leg::ProcedureLeg,
+    sql::{self, Runways},
+};
 
 pub mod approach;
 pub mod arrival;
@@ -12,6 +20,210 @@ pub struct Transition {
     legs: Vec<ProcedureLeg>,
 }
 
+/// A lightweight representation of a procedure, without any of its legs
+#[serde_with::skip_serializing_none]
+#[derive(Serialize)]
+pub struct ProcedureSummary {
+    /// The `ident` of the procedure, which uniquely identifies it within the airport which it serves
+    ident: String,
+    /// The idents of the runway transitions of this procedure, which are the runways it serves (e.g. `RW27L`)
+    runway_transitions: Vec<String>,
+    /// The idents of the enroute transitions of this procedure. For approaches, these are the approach transitions
+    enroute_transitions: Vec<String>,
+    /// The type of approach, only present for approaches
+    approach_type: Option<ApproachType>,
+    /// The smallest rnp (required navigational performance) of any leg in this procedure (excluding the missed
+    /// approach) in nautical miles
+    rnp: Option<NauticalMiles>,
+    /// Whether any leg of this procedure (excluding the missed approach) requires authorization (RNP AR)
+    authorization_required: bool,
+}
+
+/// The part of a procedure which a row belongs to, used to summarise a procedure without assembling its legs
+enum SummaryRow {
+    /// A leg of the runway transitions with the ident, which may stand for several runways
+    RunwayTransition(String),
+    /// A leg of the enroute transition with the ident. For approaches, this is an approach transition
+    EnrouteTransition(String),
+    /// A leg which is common to all transitions, or only to the runway transitions with the ident if it is set
+    Common(Option<String>),
+    /// A leg which is left out of the summary, such as a leg of the missed approach
+    Excluded,
+}
+
+impl ProcedureSummary {
+    /// Summarises the procedures at an airport from the header columns of their rows, without assembling their legs.
+    /// The summaries are in the order the procedures first appear in `data`.
+    ///
+    /// * `classify` - Finds the part of its procedure which a row belongs to, and may fill in the summary of the
+    ///   procedure, which is passed in along with it
+    fn map_summaries(
+        data: Vec<sql::ProcedureHeaders>,
+        runways: &[Runways],
+        mut classify: impl FnMut(&sql::ProcedureHeaders, &mut ProcedureSummary) -> SummaryRow,
+    ) -> Vec<ProcedureSummary> {
+        let mut procedures: Vec<(ProcedureSummary, Vec<sql::ProcedureHeaders>)> = Vec::new();
+        let mut indices = HashMap::new();
+
+        for row in data {
+            let index = *indices
+                .entry(row.procedure_identifier.clone())
+                .or_insert_with(|| {
+                    procedures.push((
+                        ProcedureSummary {
+                            ident: row.procedure_identifier.clone(),
+                            runway_transitions: Vec::new(),
+                            enroute_transitions: Vec::new(),
+                            approach_type: None,
+                            rnp: None,
+                            authorization_required: false,
+                        },
+                        Vec::new(),
+                    ));
+
+                    procedures.len() - 1
+                });
+
+            procedures[index].1.push(row);
+        }
+
+        procedures
+            .into_iter()
+            .map(|(mut summary, rows)| {
+                let mut performance = Vec::new();
+
+                for row in rows {
+                    let runway_transitions = match classify(&row, &mut summary) {
+                        SummaryRow::RunwayTransition(ident) => {
+                            runway_transition_idents(&ident, runways)
+                        }
+                        SummaryRow::Common(Some(ident)) => {
+                            common_leg_runway_idents(&ident, runways)
+                        }
+                        SummaryRow::EnrouteTransition(ident) => {
+                            if !summary.enroute_transitions.contains(&ident) {
+                                summary.enroute_transitions.push(ident);
+                            }
+
+                            Vec::new()
+                        }
+                        SummaryRow::Common(None) => Vec::new(),
+                        SummaryRow::Excluded => continue,
+                    };
+
+                    for ident in runway_transitions {
+                        if !summary.runway_transitions.contains(&ident) {
+                            summary.runway_transitions.push(ident);
+                        }
+                    }
+
+                    performance.push((row.rnp, row.authorization_required));
+                }
+
+                (summary.rnp, summary.authorization_required) =
+                    required_performance(performance.into_iter());
+
+                summary
+            })
+            .collect()
+    }
+}
+
+/// Summarises the navigation performance required to fly a set of legs, as the smallest rnp (required navigational
+/// performance) of any leg in nautical miles and whether any leg requires authorization (RNP AR).
+///
+/// The legs of the missed approach of an approach are not included by the callers, so that the summary of an approach
+/// matches its capabilities.
+fn required_performance(
+    legs: impl Iterator<Item = (Option<NauticalMiles>, Option<AuthorizationRequired>)>,
+) -> (Option<NauticalMiles>, bool) {
+    legs.fold(
+        (None, false),
+        |(rnp, authorization_required), (leg_rnp, leg_ar)| {
+            (
+                match (rnp, leg_rnp) {
+                    (Some(a), Some(b)) => Some(f64::min(a, b)),
+                    (a, b) => a.or(b),
+                },
+                authorization_required || matches!(leg_ar, Some(AuthorizationRequired::Yes)),
+            )
+        },
+    )
+}
+
+/// Finds the idents of the runways which the transition identifier of a common leg stands for.
+///
+/// `ALL` stands for every runway at the airport, any other identifier is handled like that of a runway transition.
+fn common_leg_runway_idents(transition_identifier: &str, runways: &[Runways]) -> Vec<String> {
+    if transition_identifier == "ALL" {
+        runways
+            .iter()
+            .map(|runway| runway.runway_identifier.clone())
+            .collect()
+    } else {
+        runway_transition_idents(transition_identifier, runways)
+    }
+}
+
+/// Finds the idents of the runways which a runway transition identifier stands for.
+///
+/// An identifier ending in `B` (such as `RW27B`) stands for every runway with that number. Any other identifier,
+/// including `ALL`, stands for itself.
+fn runway_transition_idents(transition_identifier: &str, runways: &[Runways]) -> Vec<String> {
+    if transition_identifier.chars().nth(4) == Some('B') {
+        runways
+            .iter()
+            .filter(|runway| runway.runway_identifier.get(0..4) == transition_identifier.get(0..4))
+            .map(|runway| runway.runway_identifier.clone())
+            .collect()
+    } else {
+        vec![transition_identifier.to_string()]
+    }
+}
+
+/// Finds the legs of the transition with the ident `ident`.
+///
+/// Returns an empty list if `ident` is `None`, or `None` if there is no transition with the ident.
+fn find_transition_legs<'a>(
+    transitions: &'a [Transition],
+    ident: &Option<String>,
+) -> Option<&'a [ProcedureLeg]> {
+    match ident {
+        Some(ident) => transitions
+            .iter()
+            .find(|transition| transition.ident == *ident)
+            .map(|transition| transition.legs.as_slice()),
+        None => Some(&[]),
+    }
+}
+
+/// Removes the procedure rows which do not apply to aircraft of `aircraft_category`.
+///
+/// Rows without an aircraft category apply to all aircraft, and no rows are removed if `aircraft_category` is `None`.
+pub fn filter_aircraft_category(
+    data: Vec<sql::Procedures>,
+    aircraft_category: Option<AircraftCategory>,
+) -> Vec<sql::Procedures> {
+    data.into_iter()
+        .filter(|row| applies_to_aircraft_category(&row.aircraft_category, aircraft_category))
+        .collect()
+}
+
+/// Checks whether or not a procedure row with the aircraft category column `category` applies to aircraft of
+/// `aircraft_category`
+pub fn applies_to_aircraft_category(
+    category: &Option<String>,
+    aircraft_category: Option<AircraftCategory>,
+) -> bool {
+    let Some(aircraft_category) = aircraft_category else {
+        return true;
+    };
+
+    category.as_ref().is_none_or(|category| {
+        category.trim().is_empty() || category.contains(aircraft_category.code())
+    })
+}
+
 /// A helper function which returns a mutable reference to an item in a vector if it can be found using the `condition`,
 /// or inserts a new item `val` into the vector and returns a mutable reference to it.
 fn mut_find_or_insert<T, P: FnMut(&T) -> bool>(vec: &mut Vec<T>, condition: P, val: T) -> &mut T {
@@ -56,53 +268,20 @@ fn apply_common_leg(
     // If it is not present, it means there are seperate runway transitions for each runway after these
     // common legs
     if let Some(transition_identifier) = transition_identifier {
-        // If the transition identifier is `ALL`, this means that this procedure is for all runways and
-        // has exactly the same legs for each runway, so we insert a runway transition for every runway
-        // at the airport
-        if transition_identifier == "ALL" {
-            for runway in runways.iter() {
-                let transition = mut_find_or_insert(
-                    runway_transitions,
-                    |transition| transition.ident == runway.runway_identifier,
-                    Transition {
-                        ident: runway.runway_identifier.clone(),
-                        legs: Vec::new(),
-                    },
-                );
-
-                transition.legs.push(leg.clone());
-            }
-        // When the identifier ends with B, this procedure is for all runways with that number
-        } else if transition_identifier.chars().nth(4) == Some('B') {
-            let target_runways = runways
-                .iter()
-                .filter(|runway| runway.runway_identifier[0..4] == transition_identifier[0..4]);
-
-            for runway in target_runways {
-                let transition = mut_find_or_insert(
-                    runway_transitions,
-                    |transition| transition.ident == runway.runway_identifier,
-                    Transition {
-                        ident: runway.runway_identifier.clone(),
-                        legs: Vec::new(),
-                    },
-                );
-
-                transition.legs.push(leg.clone());
-            }
-        // In this case, the transition identifier is for a specific runway, so we insert it as a runway
-        // transition to indicate which runway this procedure is specifically for
-        } else {
+        // The transition identifier can be `ALL`, which means this procedure has exactly the same legs for each
+        // runway, or end in `B` for all runways with that number. In both cases a runway transition is inserted for
+        // every runway it serves, otherwise it is for a specific runway
+        for ident in common_leg_runway_idents(&transition_identifier, runways) {
             let transition = mut_find_or_insert(
                 runway_transitions,
-                |transition| transition.ident == transition_identifier,
+                |transition| transition.ident == ident,
                 Transition {
-                    ident: transition_identifier.to_string(),
+                    ident: ident.clone(),
                     legs: Vec::new(),
                 },
             );
 
-            transition.legs.push(leg);
+            transition.legs.push(leg.clone());
         }
     // When there is no transiton identifier, that means there are seperate runway transitions, so these
     // legs should actually be inserted as common legs
@@ -121,29 +300,12 @@ fn apply_runway_transition_leg(
     // If transition identifier ends in B, it means this transition serves all runways with the same
     // number. To make this easier to use in an FMS, we duplicate the transitions for all runways which
     // it serves
-    if transition_identifier.chars().nth(4) == Some('B') {
-        let target_runways = runways
-            .iter()
-            .filter(|runway| runway.runway_identifier[0..4] == transition_identifier[0..4]);
-
-        for runway in target_runways {
-            let transition = mut_find_or_insert(
-                runway_transitions,
-                |transition| transition.ident == runway.runway_identifier,
-                Transition {
-                    ident: runway.runway_identifier.clone(),
-                    legs: Vec::new(),
-                },
-            );
-
-            transition.legs.push(leg.clone());
-        }
-    } else {
+    for ident in runway_transition_idents(&transition_identifier, runways) {
         let transition = mut_find_or_insert(
             runway_transitions,
-            |transition| transition.ident == transition_identifier,
+            |transition| transition.ident == ident,
             Transition {
-                ident: transition_identifier.to_string(),
+                ident: ident.clone(),
                 legs: Vec::new(),
             },
         );
@@ -151,3 +313,165 @@ fn apply_runway_transition_leg(
         transition.legs.push(leg.clone());
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{approach::map_approach_summaries, departure::map_departure_summaries, *};
+    use crate::database::{types::fixtures, utils::Coordinates};
+
+    fn runway(ident: &str) -> Runways {
+        fixtures::runway(
+            ident,
+            Coordinates {
+                lat: 0.0,
+                long: 0.0,
+            },
+            0.0,
+        )
+    }
+
+    fn runways() -> Vec<Runways> {
+        ["RW09L", "RW09R", "RW27L", "RW27R"].map(runway).into()
+    }
+
+    fn header(
+        procedure: &str,
+        route_type: &str,
+        transition: Option<&str>,
+    ) -> sql::ProcedureHeaders {
+        sql::ProcedureHeaders {
+            aircraft_category: None,
+            authorization_required: None,
+            procedure_identifier: procedure.to_string(),
+            rnp: None,
+            route_type: route_type.to_string(),
+            transition_identifier: transition.map(str::to_string),
+            waypoint_description_code: None,
+        }
+    }
+
+    #[test]
+    fn runway_transition_idents_expand_all_and_both() {
+        let runways = runways();
+
+        assert_eq!(
+            common_leg_runway_idents("ALL", &runways),
+            ["RW09L", "RW09R", "RW27L", "RW27R"]
+        );
+        assert_eq!(
+            common_leg_runway_idents("RW27B", &runways),
+            ["RW27L", "RW27R"]
+        );
+        assert_eq!(runway_transition_idents("ALL", &runways), ["ALL"]);
+        assert_eq!(
+            runway_transition_idents("RW27B", &runways),
+            ["RW27L", "RW27R"]
+        );
+        assert_eq!(runway_transition_idents("RW09L", &runways), ["RW09L"]);
+        assert!(runway_transition_idents("RW18B", &runways).is_empty());
+    }
+
+    #[test]
+    fn required_performance_of_legs() {
+        assert_eq!(required_performance([].into_iter()), (None, false));
+
+        let legs = [
+            (None, None),
+            (Some(1.0), Some(AuthorizationRequired::No)),
+            (Some(0.3), None),
+            (None, Some(AuthorizationRequired::Yes)),
+        ];
+        assert_eq!(required_performance(legs.into_iter()), (Some(0.3), true));
+    }
+
+    #[test]
+    fn aircraft_category_applicability() {
+        assert!(applies_to_aircraft_category(
+            &None,
+            Some(AircraftCategory::C)
+        ));
+        assert!(applies_to_aircraft_category(
+            &Some(" ".to_string()),
+            Some(AircraftCategory::C)
+        ));
+        assert!(applies_to_aircraft_category(
+            &Some("CD".to_string()),
+            Some(AircraftCategory::C)
+        ));
+        assert!(!applies_to_aircraft_category(
+            &Some("AB".to_string()),
+            Some(AircraftCategory::C)
+        ));
+        assert!(applies_to_aircraft_category(&Some("AB".to_string()), None));
+    }
+
+    #[test]
+    fn summarises_departures_in_order() {
+        let mut runway_leg = header("DEF2B", "4", Some("RW09L"));
+        runway_leg.rnp = Some(0.3);
+        runway_leg.authorization_required = Some(AuthorizationRequired::Yes);
+
+        let mut enroute_leg = header("ABC1A", "6", Some("XYZ"));
+        enroute_leg.rnp = Some(1.0);
+
+        let summaries = map_departure_summaries(
+            vec![
+                header("DEF2B", "4", Some("RW09L")),
+                runway_leg,
+                header("ABC1A", "5", Some("RW27B")),
+                enroute_leg,
+                header("ABC1A", "6", Some("XYZ")),
+                header("DEF2B", "2", None),
+            ],
+            &runways(),
+        );
+
+        assert_eq!(summaries.len(), 2);
+
+        let def = &summaries[0];
+        assert_eq!(def.ident, "DEF2B");
+        assert_eq!(def.runway_transitions, ["RW09L"]);
+        assert!(def.enroute_transitions.is_empty());
+        assert_eq!((def.rnp, def.authorization_required), (Some(0.3), true));
+
+        let abc = &summaries[1];
+        assert_eq!(abc.ident, "ABC1A");
+        assert_eq!(abc.runway_transitions, ["RW27L", "RW27R"]);
+        assert_eq!(abc.enroute_transitions, ["XYZ"]);
+        assert_eq!((abc.rnp, abc.authorization_required), (Some(1.0), false));
+    }
+
+    #[test]
+    fn approach_summaries_exclude_the_missed_approach() {
+        let mut final_leg = header("R27L", "R", Some("R27L"));
+        final_leg.rnp = Some(0.3);
+
+        let mut missed_leg = header("R27L", "R", Some("R27L"));
+        missed_leg.waypoint_description_code = Some("E M ".to_string());
+        missed_leg.rnp = Some(0.1);
+        missed_leg.authorization_required = Some(AuthorizationRequired::Yes);
+
+        let summaries = map_approach_summaries(vec![
+            header("R27L", "A", Some("ABC")),
+            final_leg,
+            missed_leg,
+            header("R27L", "Z", Some("R27L")),
+            header("I09R", "A", Some("DEF")),
+            header("I09R", "I", Some("I09R")),
+        ]);
+
+        assert_eq!(summaries.len(), 2);
+
+        let rnav = &summaries[0];
+        assert_eq!(rnav.runway_transitions, ["RW27L"]);
+        assert_eq!(rnav.enroute_transitions, ["ABC"]);
+        assert!(matches!(rnav.approach_type, Some(ApproachType::Rnav)));
+        assert_eq!((rnav.rnp, rnav.authorization_required), (Some(0.3), false));
+
+        let ils = &summaries[1];
+        assert_eq!(ils.runway_transitions, ["RW09R"]);
+        assert_eq!(ils.enroute_transitions, ["DEF"]);
+        assert!(matches!(ils.approach_type, Some(ApproachType::Ils)));
+        assert_eq!((ils.rnp, ils.authorization_required), (None, false));
+    }
+}
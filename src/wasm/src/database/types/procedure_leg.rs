@@ -15,21 +15,21 @@ use super::{
 #[serde_with::skip_serializing_none]
 #[derive(Serialize, Clone)]
 pub struct AltitudeContstraint {
-    altitude1: Feet,
-    altitude2: Option<Feet>,
-    descriptor: AltitudeDescriptor,
+    pub altitude1: Feet,
+    pub altitude2: Option<Feet>,
+    pub descriptor: AltitudeDescriptor,
 }
 
 #[derive(Serialize, Clone)]
 pub struct SpeedConstraint {
-    value: Knots,
-    descriptor: SpeedDescriptor,
+    pub value: Knots,
+    pub descriptor: SpeedDescriptor,
 }
 
 #[serde_with::skip_serializing_none]
 #[derive(Serialize, Clone)]
 pub struct ProcedureType {
-    authorized: Option<ProcedureTypeApproved>,
+    pub authorized: Option<ProcedureTypeApproved>,
     name: Option<String>,
 }
 
@@ -41,33 +41,33 @@ pub struct ProcedureLeg {
     overfly: bool,
 
     /// The type of leg
-    leg_type: LegType,
+    pub leg_type: LegType,
 
     /// The altitude constraint of this leg.
     ///
     /// This is a required field for any `XA` or `PI` leg
-    altitude: Option<AltitudeContstraint>,
+    pub altitude: Option<AltitudeContstraint>,
 
     /// The speed constraint of this leg
-    speed: Option<SpeedConstraint>,
+    pub speed: Option<SpeedConstraint>,
 
     /// The vertical angle constraint of this leg
-    vertical_angle: Option<Degrees>,
+    pub vertical_angle: Option<Degrees>,
 
     /// The rnp (required navigational performance) of this leg in nautical miles
-    rnp: Option<NauticalMiles>,
+    pub rnp: Option<NauticalMiles>,
     /// Authorization Required
-    ar: Option<AuthorizationRequired>,
+    pub ar: Option<AuthorizationRequired>,
 
     /// The fix that this leg terminates at
     ///
     /// This is a required field for any `XF`, `FX`, `HX` or `PI` leg.
-    fix: Option<Fix>,
+    pub fix: Option<Fix>,
 
     /// The fix that is used as the associated radio navigational aid for this leg.
     ///
     /// This is a required field for any `AF`, `CD`, `CF`, `CR`, `FX`, `PI`, `VD`, or `VR` leg
-    recommended_navaid: Option<Fix>,
+    pub recommended_navaid: Option<Fix>,
 
     /// The magnetic bearing from the `recommended_navaid` to the `fix`, or the magnetic radial from the
     /// `recommended_navaid` to intersect with in a `XR` leg
@@ -81,26 +81,26 @@ pub struct ProcedureLeg {
     course: Option<Degrees>,
 
     /// The length of the leg in nautical miles
-    length: Option<NauticalMiles>,
+    pub length: Option<NauticalMiles>,
 
     /// The time to be used when flying a hold leg, if any
     length_time: Option<Minutes>,
 
     /// The constraint on the direction of turn to be used when flying this leg
-    turn_direction: Option<TurnDirection>,
+    pub turn_direction: Option<TurnDirection>,
 
     /// The center of the arc to be flown for an `RF` leg
-    arc_center_fix: Option<Fix>,
+    pub arc_center_fix: Option<Fix>,
 
     /// The radius of the arc to be flown for an `RF` leg
-    arc_radius: Option<NauticalMiles>,
+    pub arc_radius: Option<NauticalMiles>,
 
     /// Check Appendix 3.45 for usage, making this into an enum would lose information
     gnss_fms_indication: Option<String>,
 
     /// Level of service approved (Yes (A) / No (N))
-    lnav_authorized: Option<ProcedureType>,
-    lnav_vnav_authorized: Option<ProcedureType>,
+    pub lnav_authorized: Option<ProcedureType>,
+    pub lnav_vnav_authorized: Option<ProcedureType>,
 }
 
 impl From<sql::Procedures> for ProcedureLeg {
@@ -111,7 +111,7 @@ impl From<sql::Procedures> for ProcedureLeg {
             overfly: leg
                 .waypoint_description_code
                 .clone()
-                .map_or(false, |x| x.chars().nth(1) == Some('Y')),
+                .is_some_and(|x| x.chars().nth(1) == Some('Y')),
             altitude: leg.altitude1.map(|altitude1| AltitudeContstraint {
                 altitude1,
                 altitude2: leg.altitude2,
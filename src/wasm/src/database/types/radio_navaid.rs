@@ -0,0 +1,395 @@
+use serde::Serialize;
+
+use crate::database::utils::{self, Coordinates, Degrees, Feet, NauticalMiles};
+
+use super::{
+    enums::{VhfNavaidCoverage, VhfNavaidType},
+    ils_navaid::IlsNavaid,
+    ndb_navaid::NdbNavaid,
+    vhf_navaid::VhfNavaid,
+};
+
+/// The assumed height of a navaid antenna above the ground in feet
+const ANTENNA_HEIGHT: Feet = 50.0;
+/// The service range of an ILS localizer
+const ILS_RANGE: NauticalMiles = 25.0;
+/// The furthest any navaid could be received from, used to bound searches
+pub const MAX_RECEPTION_RANGE: NauticalMiles = 250.0;
+/// The smallest angle between the lines of position of two DMEs for them to be used as a pair
+const MIN_CROSSING_ANGLE: Degrees = 30.0;
+/// The largest elevation angle from a navaid to the aircraft before the slant range error is too large to use it
+const MAX_ELEVATION_ANGLE: Degrees = 40.0;
+
+#[derive(Serialize, Clone)]
+#[serde(tag = "type")]
+/// A navaid which can be tuned on a radio, from any of the navaid tables
+pub enum RadioNavaid {
+    Vhf(VhfNavaid),
+    Ndb(NdbNavaid),
+    Ils(IlsNavaid),
+}
+
+impl RadioNavaid {
+    /// The geographic location of the transmitter of this navaid
+    pub fn location(&self) -> Coordinates {
+        match self {
+            RadioNavaid::Vhf(navaid) => navaid.location,
+            RadioNavaid::Ndb(navaid) => navaid.location,
+            RadioNavaid::Ils(navaid) => navaid.location,
+        }
+    }
+
+    /// The nominal range of this navaid, using the `range` column where it is present and otherwise the standard
+    /// service volume for the coverage class of the navaid
+    pub fn nominal_range(&self) -> NauticalMiles {
+        match self {
+            RadioNavaid::Vhf(navaid) => navaid.range.unwrap_or(match navaid.class.coverage {
+                Some(VhfNavaidCoverage::Terminal) => 25.0,
+                Some(VhfNavaidCoverage::HighAltitude) => 130.0,
+                _ => 40.0,
+            }),
+            RadioNavaid::Ndb(navaid) => navaid.range.unwrap_or(25.0),
+            RadioNavaid::Ils(_) => ILS_RANGE,
+        }
+    }
+
+    /// The elevation of the transmitter of this navaid in feet, if known
+    pub fn elevation(&self) -> Option<Feet> {
+        match self {
+            RadioNavaid::Vhf(navaid) => navaid.elevation(),
+            RadioNavaid::Ndb(_) => None,
+            RadioNavaid::Ils(navaid) => navaid.glideslope_elevation,
+        }
+    }
+
+    /// Checks whether or not this navaid can be received by an aircraft at `position` and `altitude`.
+    ///
+    /// VHF and ILS navaids are limited to the radio line of sight as well as their nominal range. NDBs propagate
+    /// beyond the line of sight so are only limited by their nominal range.
+    pub fn is_receivable(&self, position: &Coordinates, altitude: Feet) -> bool {
+        let distance = self.location().distance_to(position);
+
+        let range = match self {
+            RadioNavaid::Ndb(_) => self.nominal_range(),
+            _ => self.nominal_range().min(radio_horizon(
+                altitude - self.elevation().unwrap_or_default(),
+            )),
+        };
+
+        distance <= range
+    }
+}
+
+/// Returns the distance to the radio horizon of an aircraft `height` feet above a navaid antenna
+pub fn radio_horizon(height: Feet) -> NauticalMiles {
+    1.23 * (height.max(0.).sqrt() + ANTENNA_HEIGHT.sqrt())
+}
+
+#[derive(Serialize, Clone)]
+pub struct NavaidWithDistance {
+    /// The navaid
+    pub navaid: RadioNavaid,
+    /// The distance from the reference position to the navaid in nautical miles
+    pub distance: NauticalMiles,
+}
+
+impl NavaidWithDistance {
+    pub fn new(navaid: RadioNavaid, position: &Coordinates) -> Self {
+        let distance = navaid.location().distance_to(position);
+
+        Self { navaid, distance }
+    }
+}
+
+#[derive(Serialize)]
+pub struct DmeDmePair {
+    /// The first DME of this pair
+    pub first: NavaidWithDistance,
+    /// The second DME of this pair
+    pub second: NavaidWithDistance,
+    /// The angle between the lines of position of both DMEs, between `MIN_CROSSING_ANGLE` and 90 degrees
+    pub crossing_angle: Degrees,
+    /// The estimated error of a position fixed using this pair in nautical miles
+    pub estimated_error: NauticalMiles,
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Serialize)]
+pub struct NavUpdateCandidates {
+    /// The best DME pairs to update the position with, most accurate first
+    pub dme_dme: Vec<DmeDmePair>,
+    /// The best collocated VOR/DME to update the position with, if any
+    pub vor_dme: Option<NavaidWithDistance>,
+}
+
+/// Returns the expected error of a DME distance reading in nautical miles
+fn dme_error(distance: NauticalMiles) -> NauticalMiles {
+    0.1 + distance * 0.00125
+}
+
+/// A DME which is considered for a position update, measured from its DME or TACAN antenna
+struct DmeCandidate {
+    navaid: RadioNavaid,
+    distance: NauticalMiles,
+    bearing: Degrees,
+}
+
+impl DmeCandidate {
+    fn to_navaid_with_distance(&self) -> NavaidWithDistance {
+        NavaidWithDistance {
+            navaid: self.navaid.clone(),
+            distance: self.distance,
+        }
+    }
+}
+
+impl NavUpdateCandidates {
+    /// Selects the navaids which give the best radio position update for an aircraft at `position` and `altitude`
+    /// from `navaids`, returning at most `count` DME pairs.
+    ///
+    /// Only navaids with a DME or TACAN (excluding those of ILS and MLS installations) which are receivable and which
+    /// are not so close to the aircraft that slant range distorts their reading are considered. Distances and bearings
+    /// are measured from the DME antenna. DME pairs are ranked by the estimated error of the position fix, which
+    /// accounts for both their crossing angle and their distance.
+    pub fn new(
+        navaids: Vec<VhfNavaid>,
+        position: &Coordinates,
+        altitude: Feet,
+        count: usize,
+    ) -> Self {
+        let dmes = navaids
+            .into_iter()
+            .filter(|navaid| {
+                matches!(
+                    navaid.class.navaid_type,
+                    VhfNavaidType::VorDme
+                        | VhfNavaidType::Vortac
+                        | VhfNavaidType::Tacan
+                        | VhfNavaidType::Dme
+                )
+            })
+            .filter_map(|navaid| {
+                let dme_location = navaid.dme_location.unwrap_or(navaid.location);
+                let distance = dme_location.distance_to(position);
+                let height = altitude - navaid.elevation().unwrap_or_default();
+
+                let elevation_angle = utils::feet_to_nautical_miles(height)
+                    .atan2(distance)
+                    .to_degrees();
+
+                let navaid = RadioNavaid::Vhf(navaid);
+                let range = navaid.nominal_range().min(radio_horizon(height));
+
+                (elevation_angle <= MAX_ELEVATION_ANGLE && distance <= range).then(|| {
+                    DmeCandidate {
+                        navaid,
+                        distance,
+                        bearing: position.bearing_to(&dme_location),
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        // Pairs are ranked by index, so only the navaids of the pairs which are kept are cloned
+        let mut pairs = Vec::new();
+
+        for (first_index, first) in dmes.iter().enumerate() {
+            for (second_index, second) in dmes.iter().enumerate().skip(first_index + 1) {
+                let angle = utils::bearing_difference(first.bearing, second.bearing).abs();
+                let crossing_angle = angle.min(180.0 - angle);
+
+                if crossing_angle < MIN_CROSSING_ANGLE {
+                    continue;
+                }
+
+                let estimated_error = dme_error(first.distance).hypot(dme_error(second.distance))
+                    / crossing_angle.to_radians().sin();
+
+                pairs.push((first_index, second_index, crossing_angle, estimated_error));
+            }
+        }
+
+        pairs.sort_by(|a, b| a.3.total_cmp(&b.3));
+        pairs.truncate(count);
+
+        let dme_dme = pairs
+            .into_iter()
+            .map(
+                |(first, second, crossing_angle, estimated_error)| DmeDmePair {
+                    first: dmes[first].to_navaid_with_distance(),
+                    second: dmes[second].to_navaid_with_distance(),
+                    crossing_angle,
+                    estimated_error,
+                },
+            )
+            .collect();
+
+        let vor_dme = dmes
+            .iter()
+            .filter(|dme| match &dme.navaid {
+                RadioNavaid::Vhf(navaid) => {
+                    matches!(
+                        navaid.class.navaid_type,
+                        VhfNavaidType::VorDme | VhfNavaidType::Vortac
+                    ) && navaid.class.collocated
+                }
+                _ => false,
+            })
+            .min_by(|a, b| a.distance.total_cmp(&b.distance))
+            .map(DmeCandidate::to_navaid_with_distance);
+
+        Self { dme_dme, vor_dme }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::types::vhf_navaid::VhfNavaidClass;
+
+    fn vhf_navaid(ident: &str, class: &str, location: Coordinates) -> VhfNavaid {
+        VhfNavaid {
+            area_code: "EUR".to_string(),
+            continent: None,
+            country: None,
+            datum_code: None,
+            airport_ident: None,
+            icao_code: "ED".to_string(),
+            ident: ident.to_string(),
+            name: ident.to_string(),
+            frequency: 113.0,
+            location,
+            station_declination: None,
+            magnetic_variation: None,
+            range: None,
+            class: VhfNavaidClass::from_code(class),
+            dme_ident: Some(ident.to_string()),
+            dme_location: Some(location),
+            dme_elevation: None,
+            airport_elevation: None,
+            ilsdme_bias: None,
+        }
+    }
+
+    const ORIGIN: Coordinates = Coordinates {
+        lat: 50.0,
+        long: 8.0,
+    };
+
+    #[test]
+    fn nominal_range_by_coverage() {
+        let range =
+            |class: &str| RadioNavaid::Vhf(vhf_navaid("ABC", class, ORIGIN)).nominal_range();
+
+        assert_eq!(range("VDT"), 25.0);
+        assert_eq!(range("VDL"), 40.0);
+        assert_eq!(range("VDH"), 130.0);
+        assert_eq!(range("VD "), 40.0);
+
+        let mut published = vhf_navaid("ABC", "VDH", ORIGIN);
+        published.range = Some(60.0);
+        assert_eq!(RadioNavaid::Vhf(published).nominal_range(), 60.0);
+    }
+
+    #[test]
+    fn radio_horizon_grows_with_height() {
+        assert!((radio_horizon(0.0) - 1.23 * ANTENNA_HEIGHT.sqrt()).abs() < 1e-9);
+        assert!((radio_horizon(10000.0) - 1.23 * (100.0 + ANTENNA_HEIGHT.sqrt())).abs() < 1e-9);
+        assert_eq!(radio_horizon(-500.0), radio_horizon(0.0));
+    }
+
+    #[test]
+    fn receivable_within_range_and_line_of_sight() {
+        let navaid = RadioNavaid::Vhf(vhf_navaid("ABC", "VDH", ORIGIN));
+        let position = ORIGIN.destination(0.0, 100.0);
+
+        assert!(navaid.is_receivable(&position, 10000.0));
+        assert!(!navaid.is_receivable(&position, 2000.0));
+        assert!(!navaid.is_receivable(&ORIGIN.destination(0.0, 140.0), 40000.0));
+    }
+
+    #[test]
+    fn receivability_uses_station_elevation() {
+        let position = ORIGIN.destination(0.0, 100.0);
+
+        let mut dme_elevation = vhf_navaid("ABC", "VDH", ORIGIN);
+        dme_elevation.dme_elevation = Some(5000.0);
+        assert!(!RadioNavaid::Vhf(dme_elevation).is_receivable(&position, 10000.0));
+
+        let mut airport_elevation = vhf_navaid("ABC", "VDH", ORIGIN);
+        airport_elevation.airport_elevation = Some(5000.0);
+        assert_eq!(airport_elevation.elevation(), Some(5000.0));
+        assert!(!RadioNavaid::Vhf(airport_elevation.clone()).is_receivable(&position, 10000.0));
+
+        airport_elevation.dme_elevation = Some(100.0);
+        assert_eq!(airport_elevation.elevation(), Some(100.0));
+        assert!(RadioNavaid::Vhf(airport_elevation).is_receivable(&position, 10000.0));
+    }
+
+    fn ident(candidate: &NavaidWithDistance) -> String {
+        match &candidate.navaid {
+            RadioNavaid::Vhf(navaid) => navaid.ident.clone(),
+            _ => String::new(),
+        }
+    }
+
+    #[test]
+    fn pairs_dmes_by_estimated_error() {
+        let mut dme = vhf_navaid("DME", " D ", ORIGIN.destination(90.0, 60.0));
+        dme.range = Some(100.0);
+
+        let navaids = vec![
+            vhf_navaid("NTH", "VDH", ORIGIN.destination(0.0, 60.0)),
+            dme,
+            vhf_navaid("FAR", "VTH", ORIGIN.destination(0.0, 90.0)),
+            // ILS/DMEs and VORs without a DME are never used
+            vhf_navaid("ILS", " I ", ORIGIN.destination(180.0, 10.0)),
+            vhf_navaid("VOR", "V H", ORIGIN.destination(270.0, 10.0)),
+            // Too steep below the aircraft for an accurate slant range
+            vhf_navaid("TOP", "VDH", ORIGIN.destination(270.0, 2.0)),
+        ];
+
+        let candidates = NavUpdateCandidates::new(navaids.clone(), &ORIGIN, 20000.0, 5);
+
+        let pairs = candidates
+            .dme_dme
+            .iter()
+            .map(|pair| (ident(&pair.first), ident(&pair.second)))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            pairs,
+            vec![
+                ("NTH".to_string(), "DME".to_string()),
+                ("DME".to_string(), "FAR".to_string())
+            ]
+        );
+
+        let best = &candidates.dme_dme[0];
+        assert!((best.crossing_angle - 90.0).abs() < 1.0);
+        assert!(best.estimated_error < candidates.dme_dme[1].estimated_error);
+
+        assert_eq!(
+            NavUpdateCandidates::new(navaids, &ORIGIN, 20000.0, 1)
+                .dme_dme
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn selects_nearest_collocated_vor_dme() {
+        let navaids = vec![
+            vhf_navaid("NOT", "VDH N", ORIGIN.destination(0.0, 20.0)),
+            vhf_navaid("DME", " DH", ORIGIN.destination(90.0, 25.0)),
+            vhf_navaid("FAR", "VTH", ORIGIN.destination(180.0, 50.0)),
+            vhf_navaid("NEAR", "VDH", ORIGIN.destination(270.0, 30.0)),
+        ];
+
+        let candidates = NavUpdateCandidates::new(navaids, &ORIGIN, 20000.0, 5);
+
+        assert_eq!(
+            candidates.vor_dme.as_ref().map(ident).as_deref(),
+            Some("NEAR")
+        );
+    }
+}
@@ -1,10 +1,10 @@
 use sentry::capture_message;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-use crate::database::utils::{Coordinates, Degrees, Feet};
+use crate::database::utils::{self, Coordinates, Degrees, Feet, Knots, NauticalMiles};
 
 use super::{
-    enums::{RunwayLights, RunwaySurface, TrafficPattern},
+    enums::{RunwayLights, RunwaySurface, TrafficPattern, TurnDirection, WindReference},
     sql,
 };
 
@@ -90,3 +90,367 @@ impl From<sql::Runways> for RunwayThreshold {
         runway_new
     }
 }
+
+impl RunwayThreshold {
+    /// Checks whether or not `position` is on the surface of this runway, between the start of the runway (including a
+    /// displaced threshold of `displaced_distance`) and its end
+    pub fn is_on_surface(&self, position: &Coordinates, displaced_distance: NauticalMiles) -> bool {
+        let (along_track, cross_track) =
+            position.track_distances(&self.location, self.true_bearing);
+
+        along_track >= -displaced_distance
+            && along_track <= utils::feet_to_nautical_miles(self.length) - displaced_distance
+            && cross_track.abs() <= utils::feet_to_nautical_miles(self.width) / 2.0
+    }
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Clone)]
+pub struct RunwayEnd {
+    /// The identifier of this runway end, such as `RW18L` or `RW36R`
+    pub ident: String,
+    /// The true bearing of this runway end in degrees
+    pub true_bearing: Degrees,
+    /// The magnetic bearing of this runway end in degrees.
+    ///
+    /// This field is rounded to the nearest degree
+    pub magnetic_bearing: Degrees,
+    /// The gradient of this runway end in degrees
+    pub gradient: Degrees,
+    /// The geographic location of the physical start of the runway at this end, before any displaced threshold
+    pub start: Coordinates,
+    /// The geographic location of the physical end of the runway when travelling from this end
+    pub end: Coordinates,
+    /// The geographic location of the landing threshold of this runway end, taking into account any displaced
+    /// threshold
+    pub landing_threshold: Coordinates,
+    /// The elevation of the landing threshold of this runway end in feet
+    pub elevation: Feet,
+    /// The distance between the start of the runway and the landing threshold in feet
+    pub displaced_threshold_distance: Option<Feet>,
+    /// The height above the landing threshold at which the glidepath of this runway end crosses it in feet
+    pub threshold_crossing_height: Option<Feet>,
+    /// The identifier of the ILS, MLS or GLS serving this runway end, if any
+    pub ils_ident: Option<String>,
+    /// The category of the ILS, MLS or GLS serving this runway end, such as `1` or `3`
+    pub ils_category: Option<String>,
+    /// The traffic pattern of this runway end (v2 only)
+    pub traffic_pattern: Option<TrafficPattern>,
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Clone)]
+pub struct Runway {
+    /// The identifier of this runway made up of both of its ends, such as `09L/27R`
+    pub ident: String,
+    /// The icao prefix of the region that this runway is in.
+    pub icao_code: String,
+    /// The length of this runway in feet
+    pub length: Feet,
+    /// The width of this runway in feet
+    pub width: Feet,
+    /// Whether or not the runway has lights (v2 only)
+    pub lights: Option<RunwayLights>,
+    /// Material that the runway is made out of (v2 only)
+    pub surface: Option<RunwaySurface>,
+    /// The lower numbered end of this runway
+    pub first: RunwayEnd,
+    /// The higher numbered end of this runway, if it is present in the database
+    pub second: Option<RunwayEnd>,
+    /// The four corners of the runway surface, going clockwise from the left side of the start of `first`
+    pub polygon: Vec<Coordinates>,
+}
+
+/// Returns the identifier of the reciprocal end of a runway, such as `RW27R` for `RW09L`
+///
+/// Returns `None` if the identifier is not a valid runway identifier
+pub fn reciprocal_runway_ident(ident: &str) -> Option<String> {
+    let designator = ident.strip_prefix("RW")?;
+    let number = designator.get(0..2)?.parse::<u32>().ok()?;
+    let suffix = match designator.get(2..)? {
+        "L" => "R",
+        "R" => "L",
+        suffix => suffix,
+    };
+
+    let reciprocal = (number + 17) % 36 + 1;
+
+    Some(format!("RW{reciprocal:02}{suffix}"))
+}
+
+impl RunwayEnd {
+    /// Creates a runway end from a row of the runways table, along with the threshold it describes
+    fn from_row(runway: sql::Runways) -> (Self, RunwayThreshold) {
+        let displaced_threshold_distance = runway.displaced_threshold_distance;
+        let threshold_crossing_height = runway.threshold_crossing_height;
+        let ils_ident = runway.llz_identifier.clone();
+        let ils_category = runway.llz_mls_gls_category.clone();
+
+        let threshold = RunwayThreshold::from(runway);
+
+        let start = threshold.location.destination(
+            threshold.true_bearing + 180.0,
+            utils::feet_to_nautical_miles(displaced_threshold_distance.unwrap_or_default()),
+        );
+        let end = start.destination(
+            threshold.true_bearing,
+            utils::feet_to_nautical_miles(threshold.length),
+        );
+
+        let runway_end = Self {
+            ident: threshold.ident.clone(),
+            true_bearing: threshold.true_bearing,
+            magnetic_bearing: threshold.magnetic_bearing,
+            gradient: threshold.gradient,
+            start,
+            end,
+            landing_threshold: threshold.location,
+            elevation: threshold.elevation,
+            displaced_threshold_distance,
+            threshold_crossing_height,
+            ils_ident,
+            ils_category,
+            traffic_pattern: threshold.traffic_pattern,
+        };
+
+        (runway_end, threshold)
+    }
+}
+
+/// Pairs the runway thresholds of a single airport into runways.
+///
+/// Thresholds are paired with the threshold which has the reciprocal identifier. Thresholds with no reciprocal in the
+/// data (such as water runways or one-way runways) are returned as a runway with only one end.
+pub fn map_runways(mut data: Vec<sql::Runways>) -> Vec<Runway> {
+    data.sort_by(|a, b| a.runway_identifier.cmp(&b.runway_identifier));
+
+    let mut ends = data
+        .into_iter()
+        .map(|runway| {
+            let (end, threshold) = RunwayEnd::from_row(runway);
+            Some((threshold, end))
+        })
+        .collect::<Vec<_>>();
+
+    let mut runways = Vec::new();
+
+    for index in 0..ends.len() {
+        let Some((threshold, mut first)) = ends[index].take() else {
+            continue;
+        };
+
+        let mut second = reciprocal_runway_ident(&first.ident)
+            .and_then(|ident| {
+                ends.iter()
+                    .position(|end| end.as_ref().is_some_and(|end| end.1.ident == ident))
+            })
+            .and_then(|reciprocal_index| ends[reciprocal_index].take())
+            .map(|(_, second)| second);
+
+        // The start of the reciprocal end is more accurate than projecting the length along the bearing
+        if let Some(second) = &mut second {
+            first.end = second.start;
+            second.end = first.start;
+        }
+
+        let half_width = utils::feet_to_nautical_miles(threshold.width) / 2.0;
+        let left = first.true_bearing - 90.0;
+        let right = first.true_bearing + 90.0;
+
+        let polygon = vec![
+            first.start.destination(left, half_width),
+            first.end.destination(left, half_width),
+            first.end.destination(right, half_width),
+            first.start.destination(right, half_width),
+        ];
+
+        let ident = match &second {
+            Some(second) => format!(
+                "{}/{}",
+                first.ident.trim_start_matches("RW"),
+                second.ident.trim_start_matches("RW")
+            ),
+            None => first.ident.trim_start_matches("RW").to_string(),
+        };
+
+        runways.push(Runway {
+            ident,
+            icao_code: threshold.icao_code,
+            length: threshold.length,
+            width: threshold.width,
+            lights: threshold.lights,
+            surface: threshold.surface,
+            first,
+            second,
+            polygon,
+        });
+    }
+
+    runways
+}
+
+#[derive(Deserialize, Clone, Copy)]
+pub struct Wind {
+    /// The direction the wind is blowing from in degrees
+    pub direction: Degrees,
+    /// The steady wind speed in knots
+    pub speed: Knots,
+    /// The gust speed in knots, if any
+    pub gust: Option<Knots>,
+    /// The reference `direction` is given in, defaults to true north
+    #[serde(default)]
+    pub reference: WindReference,
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Serialize)]
+pub struct RunwayWindComponents {
+    /// The runway threshold these wind components apply to
+    pub runway: RunwayThreshold,
+    /// The component of the wind along the runway blowing towards the aircraft in knots
+    pub headwind: Knots,
+    /// The component of the wind along the runway blowing from behind the aircraft in knots
+    pub tailwind: Knots,
+    /// The component of the wind across the runway in knots
+    pub crosswind: Knots,
+    /// The side of the runway which the crosswind is blowing from
+    pub crosswind_direction: TurnDirection,
+    /// The headwind component of the gusts in knots
+    pub gust_headwind: Option<Knots>,
+    /// The tailwind component of the gusts in knots
+    pub gust_tailwind: Option<Knots>,
+    /// The crosswind component of the gusts in knots
+    pub gust_crosswind: Option<Knots>,
+}
+
+impl RunwayWindComponents {
+    /// Resolves a wind into components along and across a runway. The direction of the wind must be relative to true
+    /// north
+    pub fn new(runway: RunwayThreshold, wind: &Wind) -> Self {
+        let angle = utils::bearing_difference(runway.true_bearing, wind.direction).to_radians();
+
+        let along = angle.cos();
+        let across = angle.sin();
+
+        Self {
+            runway,
+            headwind: (wind.speed * along).max(0.),
+            tailwind: (-wind.speed * along).max(0.),
+            crosswind: (wind.speed * across).abs(),
+            crosswind_direction: if across < 0. {
+                TurnDirection::Left
+            } else {
+                TurnDirection::Right
+            },
+            gust_headwind: wind.gust.map(|gust| (gust * along).max(0.)),
+            gust_tailwind: wind.gust.map(|gust| (-gust * along).max(0.)),
+            gust_crosswind: wind.gust.map(|gust| (gust * across).abs()),
+        }
+    }
+
+    /// The highest tailwind the runway is exposed to, including gusts
+    pub fn max_tailwind(&self) -> Knots {
+        self.gust_tailwind.unwrap_or_default().max(self.tailwind)
+    }
+
+    /// The highest crosswind the runway is exposed to, including gusts
+    pub fn max_crosswind(&self) -> Knots {
+        self.gust_crosswind.unwrap_or_default().max(self.crosswind)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::types::fixtures::runway;
+
+    #[test]
+    fn positions_on_runway_surface() {
+        let threshold = RunwayThreshold::from(runway(
+            "RW09",
+            Coordinates {
+                lat: 0.0,
+                long: 0.0,
+            },
+            90.0,
+        ));
+        let origin = threshold.location;
+
+        assert!(threshold.is_on_surface(&origin.destination(90.0, 0.5), 0.0));
+        assert!(threshold.is_on_surface(&origin.destination(90.0, 0.5).destination(0.0, 0.01), 0.0));
+        assert!(
+            !threshold.is_on_surface(&origin.destination(90.0, 0.5).destination(0.0, 0.02), 0.0)
+        );
+        assert!(!threshold.is_on_surface(&origin.destination(90.0, 1.1), 0.0));
+        assert!(!threshold.is_on_surface(&origin.destination(270.0, 0.1), 0.0));
+    }
+
+    #[test]
+    fn displaced_threshold_moves_runway_surface() {
+        let threshold = RunwayThreshold::from(runway(
+            "RW09",
+            Coordinates {
+                lat: 0.0,
+                long: 0.0,
+            },
+            90.0,
+        ));
+        let origin = threshold.location;
+
+        assert!(threshold.is_on_surface(&origin.destination(270.0, 0.1), 0.2));
+        assert!(!threshold.is_on_surface(&origin.destination(90.0, 0.9), 0.2));
+    }
+
+    #[test]
+    fn reciprocal_runway_idents() {
+        assert_eq!(reciprocal_runway_ident("RW09L").as_deref(), Some("RW27R"));
+        assert_eq!(reciprocal_runway_ident("RW27R").as_deref(), Some("RW09L"));
+        assert_eq!(reciprocal_runway_ident("RW18C").as_deref(), Some("RW36C"));
+        assert_eq!(reciprocal_runway_ident("RW36").as_deref(), Some("RW18"));
+        assert_eq!(reciprocal_runway_ident("RW01W").as_deref(), Some("RW19W"));
+        assert_eq!(reciprocal_runway_ident("RW18").as_deref(), Some("RW36"));
+    }
+
+    #[test]
+    fn reciprocal_runway_ident_rejects_invalid_idents() {
+        assert_eq!(reciprocal_runway_ident("09L"), None);
+        assert_eq!(reciprocal_runway_ident("RW9"), None);
+        assert_eq!(reciprocal_runway_ident("RWAB"), None);
+    }
+
+    #[test]
+    fn pairs_reciprocal_thresholds() {
+        let west = Coordinates {
+            lat: 0.0,
+            long: 0.0,
+        };
+        let east = west.destination(90.0, 1.0);
+        let north = Coordinates {
+            lat: 0.1,
+            long: 0.0,
+        };
+
+        let runways = map_runways(vec![
+            runway("RW27", east, 270.0),
+            runway("RW18", north, 180.0),
+            runway("RW09", west, 90.0),
+        ]);
+
+        assert_eq!(runways.len(), 2);
+
+        let paired = &runways[0];
+        assert_eq!(paired.ident, "09/27");
+        assert_eq!(paired.icao_code, "K1");
+        let second = paired.second.as_ref().expect("RW27 is paired with RW09");
+        assert_eq!(paired.first.ident, "RW09");
+        assert_eq!(second.ident, "RW27");
+        assert!(paired.first.end.distance_to(&east) < 1e-6);
+        assert!(second.end.distance_to(&west) < 1e-6);
+        assert_eq!(paired.polygon.len(), 4);
+
+        let single = &runways[1];
+        assert_eq!(single.ident, "18");
+        assert!(single.second.is_none());
+        assert!(single.first.end.distance_to(&north.destination(180.0, 1.0)) < 0.01);
+    }
+}
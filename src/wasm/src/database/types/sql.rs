@@ -331,6 +331,7 @@ pub struct Holdings {
 
 #[derive(Deserialize, Debug)]
 pub struct Procedures {
+    pub aircraft_category: Option<String>,
     pub airport_identifier: String,
     pub altitude_description: Option<AltitudeDescriptor>,
     pub altitude1: Option<f64>,
@@ -378,6 +379,18 @@ pub struct Procedures {
     pub waypoint_ref_table: Option<String>,
 }
 
+/// The columns of the procedure tables which are needed to summarise a procedure, without its legs
+#[derive(Deserialize, Debug)]
+pub struct ProcedureHeaders {
+    pub aircraft_category: Option<String>,
+    pub authorization_required: Option<AuthorizationRequired>,
+    pub procedure_identifier: String,
+    pub rnp: Option<f64>,
+    pub route_type: String,
+    pub transition_identifier: Option<String>,
+    pub waypoint_description_code: Option<String>,
+}
+
 #[derive(Deserialize, Debug)]
 pub struct LocalizerMarker {
     pub area_code: String,
@@ -463,7 +476,7 @@ pub struct RestrictiveAirspace {
     pub upper_limit: Option<String>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 pub struct Runways {
     pub airport_identifier: String,
     pub area_code: Option<String>,
@@ -565,6 +578,7 @@ pub struct Waypoints {
 
 #[derive(Deserialize, Debug)]
 pub struct VhfNavaids {
+    pub airport_elevation: Option<f64>,
     pub airport_identifier: Option<String>,
     pub area_code: String,
     pub continent: Option<String>,
@@ -0,0 +1,407 @@
+use serde::Serialize;
+
+use crate::database::utils::{feet_to_nautical_miles, Coordinates, Degrees, Feet, NauticalMiles};
+
+use super::{
+    enums::{AltitudeDescriptor, LegType, TurnDirection},
+    procedure_leg::{AltitudeContstraint, ProcedureLeg, SpeedConstraint},
+};
+
+/// The steepest climb or descent gradient which is considered flyable between two altitude constraints
+pub const MAX_PROFILE_GRADIENT: Degrees = 6.0;
+
+#[serde_with::skip_serializing_none]
+#[derive(Serialize)]
+/// A point along a procedure at the termination of one of its legs
+pub struct VerticalProfilePoint {
+    /// The ident of the fix which the leg terminates at, if any
+    ident: Option<String>,
+    leg_type: LegType,
+    /// The along-track distance from the start of the procedure to this point.
+    ///
+    /// This is only present for legs which terminate at a fix, legs such as `VA` or `CA` have no fixed length.
+    distance: Option<NauticalMiles>,
+    /// The lowest altitude allowed at this point
+    min_altitude: Option<Feet>,
+    /// The highest altitude allowed at this point
+    max_altitude: Option<Feet>,
+    speed: Option<SpeedConstraint>,
+    vertical_angle: Option<Degrees>,
+}
+
+#[derive(Serialize)]
+/// A pair of altitude constraints which can not be met with a gradient of at most `MAX_PROFILE_GRADIENT`
+pub struct VerticalProfileIssue {
+    /// The index of the first point in `points`
+    from: usize,
+    /// The index of the second point in `points`
+    to: usize,
+    /// The smallest climb (positive) or descent (negative) gradient which meets both constraints
+    required_gradient: Degrees,
+}
+
+#[derive(Serialize)]
+/// The altitude and speed constraints along an assembled procedure
+pub struct VerticalProfile {
+    points: Vec<VerticalProfilePoint>,
+    /// The along-track distance from the start of the procedure to its last fix
+    total_distance: NauticalMiles,
+    issues: Vec<VerticalProfileIssue>,
+}
+
+/// Maps an altitude constraint to the lowest and highest altitude it allows
+fn map_altitude_window(altitude: &AltitudeContstraint) -> (Option<Feet>, Option<Feet>) {
+    let altitude1 = Some(altitude.altitude1);
+
+    match altitude.descriptor {
+        AltitudeDescriptor::AtAlt1
+        | AltitudeDescriptor::AtAlt1GsMslAlt2
+        | AltitudeDescriptor::AtAlt1GsInterceptAlt2
+        | AltitudeDescriptor::AtAlt1AngleAlt2 => (altitude1, altitude1),
+        AltitudeDescriptor::AtOrAboveAlt1
+        | AltitudeDescriptor::AtOrAboveAlt1GsMslAlt2
+        | AltitudeDescriptor::AtOrAboveAlt1GsInterceptAlt2
+        | AltitudeDescriptor::AtOrAboveAlt1AngleAlt2 => (altitude1, None),
+        AltitudeDescriptor::AtOrBelowAlt1 | AltitudeDescriptor::AtOrBelowAlt1AngleAlt2 => {
+            (None, altitude1)
+        }
+        AltitudeDescriptor::BetweenAlt1Alt2 => match altitude.altitude2 {
+            Some(altitude2) => (
+                Some(altitude.altitude1.min(altitude2)),
+                Some(altitude.altitude1.max(altitude2)),
+            ),
+            None => (altitude1, altitude1),
+        },
+        AltitudeDescriptor::AtOrAboveAlt2 => (altitude.altitude2, None),
+        AltitudeDescriptor::Unknown => (None, None),
+    }
+}
+
+/// Calculates the along-track length of a leg which ends at `end`, starting from `start`.
+///
+/// Published leg lengths are preferred, `RF` legs are measured along their arc and holds do not add any distance.
+fn leg_length(leg: &ProcedureLeg, start: &Coordinates, end: &Coordinates) -> NauticalMiles {
+    match leg.leg_type {
+        LegType::HA | LegType::HF | LegType::HM => 0.0,
+        LegType::RF => match (&leg.arc_center_fix, leg.arc_radius, leg.turn_direction) {
+            (Some(center), Some(radius), Some(turn_direction)) => {
+                let from = center.location.bearing_to(start);
+                let to = center.location.bearing_to(end);
+
+                let angle = match turn_direction {
+                    TurnDirection::Left => (from - to).rem_euclid(360.0),
+                    _ => (to - from).rem_euclid(360.0),
+                };
+
+                angle.to_radians() * radius
+            }
+            _ => leg.length.unwrap_or_else(|| start.distance_to(end)),
+        },
+        _ => leg.length.unwrap_or_else(|| start.distance_to(end)),
+    }
+}
+
+impl VerticalProfile {
+    /// Builds the vertical profile of a list of legs, in the order they are flown.
+    ///
+    /// Legs which do not terminate at a fix do not add any distance, so the leg after them is measured from the last
+    /// fix before them.
+    pub fn new(legs: &[&ProcedureLeg]) -> Self {
+        let mut last_location: Option<Coordinates> = None;
+        let mut total_distance = 0.0;
+
+        let points = legs
+            .iter()
+            .map(|leg| {
+                let location = leg.fix.as_ref().map(|fix| fix.location);
+
+                let distance = location.map(|location| {
+                    if let Some(last_location) = last_location {
+                        total_distance += leg_length(leg, &last_location, &location);
+                    }
+
+                    last_location = Some(location);
+
+                    total_distance
+                });
+
+                let (min_altitude, max_altitude) = leg
+                    .altitude
+                    .as_ref()
+                    .map(map_altitude_window)
+                    .unwrap_or_default();
+
+                VerticalProfilePoint {
+                    ident: leg.fix.as_ref().map(|fix| fix.ident.clone()),
+                    leg_type: leg.leg_type,
+                    distance,
+                    min_altitude,
+                    max_altitude,
+                    speed: leg.speed.clone(),
+                    vertical_angle: leg.vertical_angle,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let issues = Self::find_issues(&points);
+
+        Self {
+            points,
+            total_distance,
+            issues,
+        }
+    }
+
+    /// Finds the pairs of consecutive constrained points which can not both be met without exceeding
+    /// `MAX_PROFILE_GRADIENT`
+    fn find_issues(points: &[VerticalProfilePoint]) -> Vec<VerticalProfileIssue> {
+        let mut issues = Vec::new();
+        let mut previous: Option<(usize, NauticalMiles, &VerticalProfilePoint)> = None;
+
+        for (index, point) in points.iter().enumerate() {
+            // A window where the lowest altitude is above the highest can never be met
+            if let (Some(min), Some(max)) = (point.min_altitude, point.max_altitude) {
+                if min > max {
+                    issues.push(VerticalProfileIssue {
+                        from: index,
+                        to: index,
+                        required_gradient: 90.0,
+                    });
+                }
+            }
+
+            let Some(distance) = point.distance else {
+                continue;
+            };
+
+            if point.min_altitude.is_none() && point.max_altitude.is_none() {
+                continue;
+            }
+
+            if let Some((previous_index, previous_distance, previous_point)) = previous {
+                let climb = point
+                    .min_altitude
+                    .zip(previous_point.max_altitude)
+                    .map(|(min, max)| min - max)
+                    .filter(|climb| *climb > 0.0);
+                let descent = previous_point
+                    .min_altitude
+                    .zip(point.max_altitude)
+                    .map(|(min, max)| max - min)
+                    .filter(|descent| *descent < 0.0);
+
+                if let Some(change) = climb.or(descent) {
+                    let required_gradient = feet_to_nautical_miles(change)
+                        .atan2(distance - previous_distance)
+                        .to_degrees();
+
+                    if required_gradient.abs() > MAX_PROFILE_GRADIENT {
+                        issues.push(VerticalProfileIssue {
+                            from: previous_index,
+                            to: index,
+                            required_gradient,
+                        });
+                    }
+                }
+            }
+
+            previous = Some((index, distance, point));
+        }
+
+        issues
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::database::types::fixtures::{fix, procedure_leg, with};
+
+    fn window(
+        descriptor: AltitudeDescriptor,
+        altitude1: Feet,
+        altitude2: Option<Feet>,
+    ) -> (Option<Feet>, Option<Feet>) {
+        map_altitude_window(&AltitudeContstraint {
+            altitude1,
+            altitude2,
+            descriptor,
+        })
+    }
+
+    fn point(
+        distance: Option<NauticalMiles>,
+        min_altitude: Option<Feet>,
+        max_altitude: Option<Feet>,
+    ) -> VerticalProfilePoint {
+        VerticalProfilePoint {
+            ident: None,
+            leg_type: LegType::TF,
+            distance,
+            min_altitude,
+            max_altitude,
+            speed: None,
+            vertical_angle: None,
+        }
+    }
+
+    fn leg(leg_type: &str, fields: serde_json::Value) -> ProcedureLeg {
+        ProcedureLeg::from(procedure_leg(with(
+            json!({ "path_termination": leg_type }),
+            fields,
+        )))
+    }
+
+    #[test]
+    fn altitude_windows() {
+        assert_eq!(
+            window(AltitudeDescriptor::AtAlt1, 5000., None),
+            (Some(5000.), Some(5000.))
+        );
+        assert_eq!(
+            window(AltitudeDescriptor::AtOrAboveAlt1, 5000., None),
+            (Some(5000.), None)
+        );
+        assert_eq!(
+            window(AltitudeDescriptor::AtOrBelowAlt1, 5000., None),
+            (None, Some(5000.))
+        );
+        assert_eq!(
+            window(AltitudeDescriptor::BetweenAlt1Alt2, 9000., Some(7000.)),
+            (Some(7000.), Some(9000.))
+        );
+        assert_eq!(
+            window(AltitudeDescriptor::BetweenAlt1Alt2, 9000., None),
+            (Some(9000.), Some(9000.))
+        );
+        assert_eq!(
+            window(AltitudeDescriptor::AtOrAboveAlt2, 5000., Some(3000.)),
+            (Some(3000.), None)
+        );
+        assert_eq!(
+            window(AltitudeDescriptor::AtAlt1GsMslAlt2, 2000., Some(1800.)),
+            (Some(2000.), Some(2000.))
+        );
+        assert_eq!(
+            window(AltitudeDescriptor::Unknown, 5000., None),
+            (None, None)
+        );
+    }
+
+    #[test]
+    fn finds_steep_descents_and_climbs() {
+        let issues = VerticalProfile::find_issues(&[
+            point(Some(0.0), Some(10000.), None),
+            // Unconstrained points and points without a distance are skipped
+            point(Some(2.0), None, None),
+            point(None, Some(1000.), Some(1000.)),
+            point(Some(5.0), None, Some(3000.)),
+            point(Some(8.0), Some(9000.), None),
+        ]);
+
+        assert_eq!(issues.len(), 2);
+
+        assert_eq!((issues[0].from, issues[0].to), (0, 3));
+        assert!(issues[0].required_gradient < -MAX_PROFILE_GRADIENT);
+        let expected = -feet_to_nautical_miles(7000.).atan2(5.0).to_degrees();
+        assert!((issues[0].required_gradient - expected).abs() < 1e-9);
+
+        assert_eq!((issues[1].from, issues[1].to), (3, 4));
+        assert!(issues[1].required_gradient > MAX_PROFILE_GRADIENT);
+    }
+
+    #[test]
+    fn accepts_flyable_gradients() {
+        let issues = VerticalProfile::find_issues(&[
+            point(Some(0.0), Some(10000.), None),
+            point(Some(20.0), None, Some(4000.)),
+            point(Some(30.0), Some(3000.), Some(5000.)),
+        ]);
+
+        assert!(issues.is_empty());
+    }
+
+    #[test]
+    fn finds_impossible_windows() {
+        let issues = VerticalProfile::find_issues(&[point(None, Some(6000.), Some(5000.))]);
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(
+            (issues[0].from, issues[0].to, issues[0].required_gradient),
+            (0, 0, 90.0)
+        );
+    }
+
+    #[test]
+    fn measures_leg_lengths() {
+        let center = Coordinates {
+            lat: 0.0,
+            long: 0.0,
+        };
+        let start = center.destination(0.0, 2.0);
+        let end = center.destination(90.0, 2.0);
+
+        let arc = |turn_direction: &str| {
+            let center_fix = json!({
+                "center_waypoint": "CTR",
+                "center_waypoint_icao_code": "K1",
+                "center_waypoint_latitude": center.lat,
+                "center_waypoint_longitude": center.long,
+                "center_waypoint_ref_table": "EA",
+                "arc_radius": 2.0,
+                "turn_direction": turn_direction,
+            });
+
+            leg("RF", with(fix("END", "EA", end), center_fix))
+        };
+
+        assert!((leg_length(&arc("R"), &start, &end) - std::f64::consts::PI).abs() < 1e-3);
+        assert!((leg_length(&arc("L"), &start, &end) - 3.0 * std::f64::consts::PI).abs() < 1e-3);
+
+        let published = leg(
+            "TF",
+            json!({ "route_distance_holding_distance_time": "D", "distance_time": 7.5 }),
+        );
+        assert_eq!(leg_length(&published, &start, &end), 7.5);
+
+        let direct = leg("TF", json!({}));
+        assert!((leg_length(&direct, &start, &end) - start.distance_to(&end)).abs() < 1e-9);
+
+        assert_eq!(leg_length(&leg("HM", json!({})), &start, &end), 0.0);
+    }
+
+    #[test]
+    fn builds_profile_distances() {
+        let first = Coordinates {
+            lat: 0.0,
+            long: 0.0,
+        };
+        let second = first.destination(90.0, 10.0);
+
+        let legs = [
+            leg("IF", fix("FIRST", "EA", first)),
+            leg(
+                "VA",
+                json!({ "altitude1": 3000.0, "altitude_description": "+" }),
+            ),
+            leg("TF", fix("SECOND", "EA", second)),
+            leg("HM", fix("SECOND", "EA", second)),
+        ];
+
+        let profile = VerticalProfile::new(&legs.iter().collect::<Vec<_>>());
+
+        let distances = profile
+            .points
+            .iter()
+            .map(|point| point.distance)
+            .collect::<Vec<_>>();
+        assert_eq!(distances[0], Some(0.0));
+        assert_eq!(distances[1], None);
+        assert!((distances[2].unwrap() - 10.0).abs() < 1e-6);
+        assert_eq!(distances[3], distances[2]);
+        assert!((profile.total_distance - 10.0).abs() < 1e-6);
+        assert_eq!(profile.points[1].min_altitude, Some(3000.));
+    }
+}
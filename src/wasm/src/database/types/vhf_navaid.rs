@@ -1,12 +1,72 @@
 use sentry::capture_message;
 use serde::Serialize;
 
-use crate::database::utils::{Coordinates, Degrees, MegaHertz, NauticalMiles};
+use crate::database::utils::{Coordinates, Degrees, Feet, MegaHertz, NauticalMiles};
 
-use super::sql;
+use super::{
+    enums::{VhfNavaidCoverage, VhfNavaidType},
+    sql,
+};
 
 #[serde_with::skip_serializing_none]
-#[derive(Serialize)]
+#[derive(Serialize, Copy, Clone)]
+/// The decoded form of the navaid class field of a `VhfNavaid`
+pub struct VhfNavaidClass {
+    /// The type of facility which makes up this navaid
+    pub navaid_type: VhfNavaidType,
+    /// The published coverage of this navaid, if any
+    pub coverage: Option<VhfNavaidCoverage>,
+    /// Whether or not voice can be transmitted on the frequency of this navaid
+    pub voice: bool,
+    /// Whether or not a scheduled or automatic weather broadcast is transmitted on the frequency of this navaid
+    pub weather_broadcast: bool,
+    /// Whether or not the DME of this navaid is biased, as is the case for some ILS/DME installations
+    pub biased: bool,
+    /// Whether or not the VOR and DME/TACAN components of this navaid are collocated
+    pub collocated: bool,
+}
+
+impl VhfNavaidClass {
+    /// Decodes a navaid class code (see Section 5.35 of ARINC 424) such as `VDHW` or ` IT`
+    ///
+    /// Blank columns may be trimmed from the end of the code, in which case they are treated as blank.
+    pub fn from_code(code: &str) -> Self {
+        let column = |index: usize| code.chars().nth(index).unwrap_or(' ');
+
+        let coverage = match column(2) {
+            'T' => Some(VhfNavaidCoverage::Terminal),
+            'L' => Some(VhfNavaidCoverage::LowAltitude),
+            'H' => Some(VhfNavaidCoverage::HighAltitude),
+            'U' => Some(VhfNavaidCoverage::Undefined),
+            'C' => Some(VhfNavaidCoverage::IlsTacan),
+            _ => None,
+        };
+
+        let navaid_type = match (column(0), column(1)) {
+            ('V', ' ') => VhfNavaidType::Vor,
+            ('V', 'D') => VhfNavaidType::VorDme,
+            ('V', 'T' | 'M') => VhfNavaidType::Vortac,
+            (_, 'T' | 'M') => VhfNavaidType::Tacan,
+            (_, 'D') => VhfNavaidType::Dme,
+            (_, 'I') if coverage == Some(VhfNavaidCoverage::IlsTacan) => VhfNavaidType::IlsTacan,
+            (_, 'I') => VhfNavaidType::IlsDme,
+            (_, 'N' | 'P') => VhfNavaidType::MlsDme,
+            _ => VhfNavaidType::Unknown,
+        };
+
+        Self {
+            navaid_type,
+            coverage,
+            voice: column(3) != 'W',
+            weather_broadcast: matches!(column(3), 'A' | 'B'),
+            biased: column(3) == 'D',
+            collocated: column(4) != 'N',
+        }
+    }
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Clone)]
 pub struct VhfNavaid {
     /// Represents the geographic region in which this VhfNavaid is located
     pub area_code: String,
@@ -34,6 +94,29 @@ pub struct VhfNavaid {
     pub magnetic_variation: Option<Degrees>,
     /// VOR range (v2 only)
     pub range: Option<NauticalMiles>,
+    /// The decoded class of this `VhfNavaid`, describing which facilities it consists of and their coverage
+    pub class: VhfNavaidClass,
+    /// The identifier of the DME or TACAN component of this `VhfNavaid`, if it has one
+    pub dme_ident: Option<String>,
+    /// The geographic location of the DME or TACAN antenna of this `VhfNavaid`, if it has one
+    pub dme_location: Option<Coordinates>,
+    /// The elevation of the DME or TACAN antenna of this `VhfNavaid` in feet
+    pub dme_elevation: Option<Feet>,
+    /// The elevation of the airport this `VhfNavaid` is associated with in feet, if it was fetched along with it
+    pub airport_elevation: Option<Feet>,
+    /// The distance in nautical miles which is subtracted from the DME reading of a biased ILS/DME, so that it reads
+    /// zero at the runway threshold instead of at the antenna
+    pub ilsdme_bias: Option<NauticalMiles>,
+}
+
+impl VhfNavaid {
+    /// The elevation of the station of this `VhfNavaid` in feet, if known.
+    ///
+    /// The VHF navaid table only records the elevation of the DME or TACAN antenna, so the elevation of the associated
+    /// airport is used for stations without one.
+    pub fn elevation(&self) -> Option<Feet> {
+        self.dme_elevation.or(self.airport_elevation)
+    }
 }
 
 impl From<sql::VhfNavaids> for VhfNavaid {
@@ -71,6 +154,15 @@ impl From<sql::VhfNavaids> for VhfNavaid {
             magnetic_variation: navaid.magnetic_variation,
             range: navaid.range,
             datum_code: navaid.datum_code,
+            class: VhfNavaidClass::from_code(&navaid.navaid_class),
+            dme_ident: navaid.dme_ident,
+            dme_location: navaid
+                .dme_latitude
+                .zip(navaid.dme_longitude)
+                .map(|(lat, long)| Coordinates { lat, long }),
+            dme_elevation: navaid.dme_elevation,
+            airport_elevation: navaid.airport_elevation,
+            ilsdme_bias: navaid.ilsdme_bias,
         };
 
         if error_in_row {
@@ -88,3 +180,88 @@ impl From<sql::VhfNavaids> for VhfNavaid {
         new_navaid
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_navaid_types() {
+        let cases = [
+            ("V  ", VhfNavaidType::Vor),
+            ("VD ", VhfNavaidType::VorDme),
+            ("VT ", VhfNavaidType::Vortac),
+            ("VM ", VhfNavaidType::Vortac),
+            (" T ", VhfNavaidType::Tacan),
+            (" M ", VhfNavaidType::Tacan),
+            (" D ", VhfNavaidType::Dme),
+            (" I ", VhfNavaidType::IlsDme),
+            (" IC", VhfNavaidType::IlsTacan),
+            (" N ", VhfNavaidType::MlsDme),
+            (" P ", VhfNavaidType::MlsDme),
+            ("", VhfNavaidType::Unknown),
+        ];
+
+        for (code, navaid_type) in cases {
+            assert_eq!(
+                VhfNavaidClass::from_code(code).navaid_type,
+                navaid_type,
+                "{code:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn decodes_coverage() {
+        assert_eq!(
+            VhfNavaidClass::from_code("VDT").coverage,
+            Some(VhfNavaidCoverage::Terminal)
+        );
+        assert_eq!(
+            VhfNavaidClass::from_code("VDL").coverage,
+            Some(VhfNavaidCoverage::LowAltitude)
+        );
+        assert_eq!(
+            VhfNavaidClass::from_code("VDH").coverage,
+            Some(VhfNavaidCoverage::HighAltitude)
+        );
+        assert_eq!(
+            VhfNavaidClass::from_code("VDU").coverage,
+            Some(VhfNavaidCoverage::Undefined)
+        );
+        assert_eq!(
+            VhfNavaidClass::from_code(" IC").coverage,
+            Some(VhfNavaidCoverage::IlsTacan)
+        );
+        assert_eq!(VhfNavaidClass::from_code("VD").coverage, None);
+    }
+
+    #[test]
+    fn decodes_voice_weather_and_bias() {
+        let plain = VhfNavaidClass::from_code("VDH  ");
+        assert!(plain.voice);
+        assert!(!plain.weather_broadcast);
+        assert!(!plain.biased);
+        assert!(plain.collocated);
+
+        let no_voice = VhfNavaidClass::from_code("VDHW ");
+        assert!(!no_voice.voice);
+
+        let weather = VhfNavaidClass::from_code("VDHA ");
+        assert!(weather.voice);
+        assert!(weather.weather_broadcast);
+        assert!(VhfNavaidClass::from_code("VDHB ").weather_broadcast);
+
+        assert!(VhfNavaidClass::from_code(" I D ").biased);
+
+        assert!(!VhfNavaidClass::from_code("VDH N").collocated);
+    }
+
+    #[test]
+    fn treats_trimmed_columns_as_blank() {
+        let class = VhfNavaidClass::from_code("VDH");
+        assert!(class.voice);
+        assert!(!class.biased);
+        assert!(class.collocated);
+    }
+}
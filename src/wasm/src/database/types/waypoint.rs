@@ -1,8 +1,133 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::database::utils::{Coordinates, Degrees};
 
-use super::sql;
+use super::{enums::WaypointUsage, sql};
+
+#[derive(Serialize, Copy, Clone, Default)]
+/// The decoded form of the waypoint type field of a `Waypoint` (see Section 5.42 of ARINC 424)
+pub struct WaypointType {
+    /// Whether or not this waypoint is an RNAV waypoint
+    pub rnav: bool,
+    /// Whether or not this waypoint is a named intersection
+    pub named_intersection: bool,
+    /// Whether or not this waypoint is an unnamed, charted intersection
+    pub unnamed_charted: bool,
+    /// Whether or not this waypoint is an uncharted airway intersection
+    pub uncharted_airway_intersection: bool,
+    /// Whether or not this waypoint is a VFR reporting point
+    pub vfr_reporting_point: bool,
+    /// Whether or not this waypoint is an NDB being used as a waypoint
+    pub ndb: bool,
+    /// Whether or not this waypoint is the center fix of an arc
+    pub arc_center: bool,
+    /// Whether or not this waypoint is an initial approach fix
+    pub initial_approach_fix: bool,
+    /// Whether or not this waypoint is an intermediate approach fix
+    pub intermediate_approach_fix: bool,
+    /// Whether or not this waypoint is a final approach fix
+    pub final_approach_fix: bool,
+    /// Whether or not this waypoint is a final approach course fix
+    pub final_approach_course_fix: bool,
+    /// Whether or not this waypoint is a missed approach fix
+    pub missed_approach_fix: bool,
+    /// Whether or not this waypoint is an off-route intersection
+    pub off_route: bool,
+    /// Whether or not this waypoint is an oceanic entry or exit point
+    pub oceanic_entry_exit: bool,
+    /// Whether or not this waypoint lies on an FIR/UIR or controlled airspace boundary
+    pub fir_uir_boundary: bool,
+    /// Whether or not this waypoint is a full or half degree latitude/longitude intersection
+    pub lat_long_intersection: bool,
+    /// Whether or not this waypoint is used in a SID
+    pub sid: bool,
+    /// Whether or not this waypoint is used in a STAR
+    pub star: bool,
+    /// Whether or not this waypoint is used in an approach
+    pub approach: bool,
+}
+
+impl WaypointType {
+    /// Decodes a waypoint type code such as `W  `, `R  ` or `WIF`
+    pub fn from_code(code: &str) -> Self {
+        let column = |index: usize| code.chars().nth(index).unwrap_or(' ');
+
+        Self {
+            rnav: matches!(column(0), 'W' | 'C'),
+            named_intersection: matches!(column(0), 'R' | 'C'),
+            unnamed_charted: column(0) == 'I',
+            uncharted_airway_intersection: column(0) == 'U',
+            vfr_reporting_point: column(0) == 'V',
+            ndb: column(0) == 'N',
+            arc_center: column(0) == 'A',
+            initial_approach_fix: matches!(column(1), 'B' | 'I' | 'K' | 'N'),
+            intermediate_approach_fix: matches!(column(1), 'D' | 'L'),
+            final_approach_fix: matches!(column(1), 'A' | 'B'),
+            final_approach_course_fix: matches!(column(1), 'C' | 'K' | 'L'),
+            missed_approach_fix: matches!(column(1), 'M' | 'N'),
+            off_route: matches!(column(1), 'E' | 'F'),
+            oceanic_entry_exit: column(1) == 'O',
+            fir_uir_boundary: column(1) == 'U',
+            lat_long_intersection: matches!(column(1), 'V' | 'W'),
+            sid: matches!(column(2), 'D' | 'Z'),
+            star: matches!(column(2), 'E' | 'Z'),
+            approach: matches!(column(2), 'F' | 'Z'),
+        }
+    }
+}
+
+/// Decodes a waypoint usage code (see Section 5.82 of ARINC 424), treating a blank usage as terminal only
+fn map_waypoint_usage(code: Option<&str>) -> WaypointUsage {
+    match code.and_then(|code| code.trim_end().chars().last()) {
+        Some('H') => WaypointUsage::High,
+        Some('L') => WaypointUsage::Low,
+        Some('B') => WaypointUsage::Both,
+        _ => WaypointUsage::Terminal,
+    }
+}
+
+#[derive(Deserialize, Default)]
+/// A filter which can be applied to waypoint queries. Each flag which is set must match the corresponding flag of the
+/// `WaypointType`, and flags which are not set are ignored.
+pub struct WaypointTypeFilter {
+    pub rnav: Option<bool>,
+    pub named_intersection: Option<bool>,
+    pub unnamed_charted: Option<bool>,
+    pub vfr_reporting_point: Option<bool>,
+    pub initial_approach_fix: Option<bool>,
+    pub final_approach_fix: Option<bool>,
+    pub fir_uir_boundary: Option<bool>,
+    pub terminal_procedure: Option<bool>,
+    /// If set, only waypoints with one of these usages are included
+    pub usage: Option<Vec<WaypointUsage>>,
+}
+
+impl WaypointTypeFilter {
+    /// Checks whether or not a `Waypoint` passes this filter
+    pub fn matches(&self, waypoint: &Waypoint) -> bool {
+        let flag = |filter: Option<bool>, value: bool| filter.is_none_or(|filter| filter == value);
+        let waypoint_type = &waypoint.waypoint_type;
+
+        flag(self.rnav, waypoint_type.rnav)
+            && flag(self.named_intersection, waypoint_type.named_intersection)
+            && flag(self.unnamed_charted, waypoint_type.unnamed_charted)
+            && flag(self.vfr_reporting_point, waypoint_type.vfr_reporting_point)
+            && flag(
+                self.initial_approach_fix,
+                waypoint_type.initial_approach_fix,
+            )
+            && flag(self.final_approach_fix, waypoint_type.final_approach_fix)
+            && flag(self.fir_uir_boundary, waypoint_type.fir_uir_boundary)
+            && flag(
+                self.terminal_procedure,
+                waypoint_type.sid || waypoint_type.star || waypoint_type.approach,
+            )
+            && self
+                .usage
+                .as_ref()
+                .is_none_or(|usage| usage.contains(&waypoint.usage))
+    }
+}
 
 #[serde_with::skip_serializing_none]
 #[derive(Serialize)]
@@ -27,6 +152,10 @@ pub struct Waypoint {
     pub location: Coordinates,
     /// Magnetic variation (v2 only)
     pub magnetic_variation: Option<Degrees>,
+    /// The decoded type of this Waypoint
+    pub waypoint_type: WaypointType,
+    /// The airway structure this Waypoint is used in, or `Terminal` if it is only used in terminal procedures
+    pub usage: WaypointUsage,
 }
 
 impl From<sql::Waypoints> for Waypoint {
@@ -51,6 +180,60 @@ impl From<sql::Waypoints> for Waypoint {
             country: waypoint.country,
             magnetic_variation: waypoint.magnetic_varation,
             datum_code: waypoint.datum_code,
+            waypoint_type: WaypointType::from_code(&waypoint.waypoint_type),
+            usage: map_waypoint_usage(waypoint.waypoint_usage.as_deref()),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_waypoint_type_columns() {
+        let rnav = WaypointType::from_code("W  ");
+        assert!(rnav.rnav);
+        assert!(!rnav.named_intersection);
+
+        let combined = WaypointType::from_code("C  ");
+        assert!(combined.rnav);
+        assert!(combined.named_intersection);
+
+        let approach = WaypointType::from_code("WBF");
+        assert!(approach.initial_approach_fix);
+        assert!(approach.final_approach_fix);
+        assert!(!approach.missed_approach_fix);
+        assert!(approach.approach);
+        assert!(!approach.sid);
+
+        let missed = WaypointType::from_code("RN ");
+        assert!(missed.initial_approach_fix);
+        assert!(missed.missed_approach_fix);
+
+        let all_procedures = WaypointType::from_code("R Z");
+        assert!(all_procedures.sid);
+        assert!(all_procedures.star);
+        assert!(all_procedures.approach);
+    }
+
+    #[test]
+    fn decodes_trimmed_waypoint_type_codes() {
+        let named = WaypointType::from_code("R");
+        assert!(named.named_intersection);
+        assert!(!named.initial_approach_fix);
+        assert!(!named.sid);
+
+        let empty = WaypointType::from_code("");
+        assert!(!empty.rnav && !empty.named_intersection && !empty.approach);
+    }
+
+    #[test]
+    fn decodes_waypoint_usage() {
+        assert_eq!(map_waypoint_usage(Some("H")), WaypointUsage::High);
+        assert_eq!(map_waypoint_usage(Some(" L")), WaypointUsage::Low);
+        assert_eq!(map_waypoint_usage(Some("RB ")), WaypointUsage::Both);
+        assert_eq!(map_waypoint_usage(Some(" ")), WaypointUsage::Terminal);
+        assert_eq!(map_waypoint_usage(None), WaypointUsage::Terminal);
+    }
+}
@@ -16,6 +16,19 @@ pub fn feet_to_meters(metres: Meters) -> Feet {
     metres / 3.28084
 }
 
+pub fn feet_to_nautical_miles(feet: Feet) -> NauticalMiles {
+    feet / 6076.12
+}
+
+pub fn meters_to_nautical_miles(meters: Meters) -> NauticalMiles {
+    meters / 1852.0
+}
+
+/// Returns the signed difference `to - from` between two bearings in the range [-180, 180)
+pub fn bearing_difference(from: Degrees, to: Degrees) -> Degrees {
+    (to - from + 180.0).rem_euclid(360.0) - 180.0
+}
+
 #[derive(Serialize, Deserialize, Debug, Copy, Clone, Default)]
 pub struct Coordinates {
     pub lat: Degrees,
@@ -79,12 +92,65 @@ impl Coordinates {
         let delta_long: Degrees = (other.long - self.long).to_radians();
 
         let a = (delta_lat / 2.0).sin().powi(2)
-            + self.lat.to_radians().cos().powi(2) * (delta_long / 2.0).sin().powi(2);
+            + self.lat.to_radians().cos()
+                * other.lat.to_radians().cos()
+                * (delta_long / 2.0).sin().powi(2);
 
         let c = 2.0 * a.sqrt().atan2((1.0 - a).sqrt());
 
         EARTH_RADIUS * c
     }
+
+    /// Returns the initial true bearing of the great circle path from these coordinates to `other`
+    pub fn bearing_to(&self, other: &Coordinates) -> Degrees {
+        let lat1 = self.lat.to_radians();
+        let lat2 = other.lat.to_radians();
+        let delta_long: Radians = (other.long - self.long).to_radians();
+
+        let y = delta_long.sin() * lat2.cos();
+        let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * delta_long.cos();
+
+        y.atan2(x).to_degrees().rem_euclid(360.0)
+    }
+
+    /// Returns the coordinates reached by travelling `distance` along a great circle with an initial true `bearing`
+    pub fn destination(&self, bearing: Degrees, distance: NauticalMiles) -> Coordinates {
+        let angular_distance: Radians = distance / EARTH_RADIUS;
+        let bearing: Radians = bearing.to_radians();
+        let lat1 = self.lat.to_radians();
+        let long1 = self.long.to_radians();
+
+        let lat2 = (lat1.sin() * angular_distance.cos()
+            + lat1.cos() * angular_distance.sin() * bearing.cos())
+        .asin();
+        let long2 = long1
+            + (bearing.sin() * angular_distance.sin() * lat1.cos())
+                .atan2(angular_distance.cos() - lat1.sin() * lat2.sin());
+
+        Coordinates {
+            lat: lat2.to_degrees(),
+            long: bearing_difference(0.0, long2.to_degrees()),
+        }
+    }
+
+    /// Returns the along-track and cross-track distance of these coordinates relative to a great circle path starting
+    /// at `origin` with an initial true `bearing`. The cross-track distance is positive to the right of the path.
+    pub fn track_distances(
+        &self,
+        origin: &Coordinates,
+        bearing: Degrees,
+    ) -> (NauticalMiles, NauticalMiles) {
+        let angular_distance: Radians = origin.distance_to(self) / EARTH_RADIUS;
+        let delta_bearing: Radians = (origin.bearing_to(self) - bearing).to_radians();
+
+        let cross_track = (angular_distance.sin() * delta_bearing.sin()).asin();
+        let along_track = (angular_distance.cos() / cross_track.cos())
+            .clamp(-1.0, 1.0)
+            .acos()
+            .copysign(delta_bearing.cos());
+
+        (along_track * EARTH_RADIUS, cross_track * EARTH_RADIUS)
+    }
 }
 
 pub fn fetch_row<T>(stmt: &mut rusqlite::Statement, params: impl rusqlite::Params) -> Result<T>
@@ -136,3 +202,62 @@ pub fn range_query_where(center: &Coordinates, range: NauticalMiles, prefix: &st
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn bearing_differences() {
+        assert_close(bearing_difference(350.0, 10.0), 20.0, 1e-9);
+        assert_close(bearing_difference(10.0, 350.0), -20.0, 1e-9);
+        assert_close(bearing_difference(90.0, 270.0), -180.0, 1e-9);
+    }
+
+    #[test]
+    fn unit_conversions() {
+        assert_close(meters_to_nautical_miles(1852.0), 1.0, 1e-9);
+        assert_close(feet_to_nautical_miles(6076.12), 1.0, 1e-9);
+    }
+
+    #[test]
+    fn destination_and_distance_agree() {
+        let origin = Coordinates {
+            lat: 51.47,
+            long: -0.45,
+        };
+        let destination = origin.destination(45.0, 10.0);
+
+        assert_close(origin.distance_to(&destination), 10.0, 1e-6);
+        assert_close(origin.bearing_to(&destination), 45.0, 1e-3);
+    }
+
+    #[test]
+    fn track_distances_relative_to_path() {
+        let origin = Coordinates {
+            lat: 0.0,
+            long: 0.0,
+        };
+
+        let (along_track, cross_track) = origin
+            .destination(90.0, 2.0)
+            .destination(180.0, 0.5)
+            .track_distances(&origin, 90.0);
+        assert_close(along_track, 2.0, 1e-3);
+        assert_close(cross_track, 0.5, 1e-3);
+
+        let (along_track, cross_track) = origin
+            .destination(270.0, 1.0)
+            .destination(0.0, 0.25)
+            .track_distances(&origin, 90.0);
+        assert_close(along_track, -1.0, 1e-3);
+        assert_close(cross_track, -0.25, 1e-3);
+    }
+}
@@ -1,6 +1,8 @@
 use std::{
     fs::{self, File, OpenOptions},
-    io::{BufReader, Write},
+    io::{BufRead, BufReader, Cursor, Read, Seek, SeekFrom, Write},
+    path::Path,
+    time::Instant,
 };
 
 use anyhow::{anyhow, Context, Result};
@@ -8,17 +10,24 @@ use msfs::network::NetworkRequestBuilder;
 use once_cell::sync::Lazy;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::{json, Value};
-use zip::ZipArchive;
+use zip::{read::ZipFile, result::ZipError, ZipArchive};
 
 use crate::{
     database::{
-        Airport, Airway, Approach, Arrival, Communication, ControlledAirspace, Coordinates,
-        DatabaseInfo, Departure, Gate, GlsNavaid, NdbNavaid, PathPoint, RestrictiveAirspace,
-        RunwayThreshold, VhfNavaid, Waypoint, DATABASE_STATE, WORK_CYCLE_JSON_PATH, WORK_DB_PATH,
-        WORK_NAVIGATION_DATA_FOLDER,
+        ensure_staged_cycle_info, install_staged_navigation_data_files,
+        verify_staged_navigation_data, AircraftCategory, Airport, AirportDetails,
+        AirportDetailsInclude, AirportSearch, Airway, Approach, Arrival, Communication,
+        ControlledAirspace, Coordinates, CycleInfo, DatabaseInfo, Departure, Gate, GlsNavaid,
+        InstalledNavigationData, NavUpdateCandidates, NavaidWithDistance, NavigationDataSource,
+        NdbNavaid, PathPoint, ProcedureKind, ProcedureSummary, RestrictiveAirspace, Runway,
+        RunwaySurface, RunwayThreshold, RunwayWindComponents, VerticalProfile, VhfNavaid, Waypoint,
+        WaypointTypeFilter, Wind, DATABASE_STATE, WORK_DB_PATH, WORK_STAGING_CYCLE_JSON_PATH,
+        WORK_STAGING_DB_PATH, WORK_STAGING_FOLDER,
     },
     futures::AsyncNetworkRequest,
-    DownloadProgressEvent, InterfaceEvent,
+    streaming::{StreamStatus, StreamingExtractor},
+    DownloadPhase, DownloadPhaseEvent, DownloadProgressEvent, ExtractionProgressEvent,
+    InterfaceEvent,
 };
 
 /// The URL to get the latest available cycle number
@@ -27,9 +36,30 @@ const LATEST_CYCLE_ENDPOINT: &str = "https://navdata.api.navigraph.com/info";
 /// The path to the temporary download file
 const DOWNLOAD_TEMP_FILE_PATH: &str = "\\work/ng_download.temp";
 
+/// The path to the manifest which tracks the progress of the download in the temp file, to allow resuming it
+const DOWNLOAD_MANIFEST_PATH: &str = "\\work/ng_download.manifest.json";
+/// The prefixes of the paths which navigation data can be installed from: the package and work folders
+const INSTALL_FROM_FILE_PATH_PREFIXES: [&str; 3] = [".\\", "\\work/", "\\work\\"];
+
 /// The max size in bytes of each request during the download function (set to 4MB curently)
 const DOWNLOAD_CHUNK_SIZE_BYTES: usize = 4 * 1024 * 1024;
 
+/// The amount of extracted bytes between each extraction progress event (set to 4MB currently)
+const EXTRACTION_PROGRESS_INTERVAL_BYTES: u64 = 4 * 1024 * 1024;
+
+/// The magic bytes at the start of a zip file
+const ZIP_MAGIC: [u8; 4] = [0x50, 0x4b, 0x03, 0x04];
+/// The magic bytes at the start of a zstd frame
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+/// The magic bytes at the start of a SQLite database
+const SQLITE_MAGIC: &[u8] = b"SQLite format 3\0";
+/// The size of a tar header block
+const TAR_BLOCK_SIZE: usize = 512;
+/// The offset of the magic bytes in a tar header block
+const TAR_MAGIC_OFFSET: usize = 257;
+/// The magic bytes of a tar header block
+const TAR_MAGIC: &[u8] = b"ustar";
+
 /// The trait definition for a function that can be called through the navigation data interface
 trait Function: DeserializeOwned {
     type ReturnType: Serialize;
@@ -58,50 +88,110 @@ trait Function: DeserializeOwned {
 
     /// The main function entry
     async fn run(&mut self) -> Result<Self::ReturnType>;
+
+    /// Any custom cleanup logic to call when the function is cancelled before it finishes
+    fn on_cancel() {}
 }
 
 #[derive(Deserialize)]
 pub struct DownloadNavigationData {
     url: String,
+    /// The expected CRC32 checksum of the downloaded file, as a hexadecimal string
+    checksum: Option<String>,
 }
 
 impl Function for DownloadNavigationData {
     type ReturnType = ();
 
     async fn run(&mut self) -> Result<Self::ReturnType> {
-        self.download_to_temp().await?;
+        let result = self.download_and_install().await;
 
-        // Only close connection if DATABASE_STATE has already been initialized - otherwise we end up unnecessarily copying the bundled data and instantly replacing it (due to initialization logic in database state)
-        if Lazy::get(&DATABASE_STATE).is_some() {
-            // Drop the current database. We don't do this before the download as there is a chance it will fail, and then we end up with no database open.
-            DATABASE_STATE
-                .try_lock()
-                .map_err(|_| anyhow!("can't lock DATABASE_STATE"))?
-                .close_connection()?;
+        send_final_download_phase(&result)?;
+
+        result
+    }
+
+    fn on_cancel() {
+        // A cancelled download is not resumed, so clean up everything it may have written. The download future, and
+        // with it any file it had open, is dropped before this is called
+        let _ = fs::remove_file(DOWNLOAD_TEMP_FILE_PATH);
+        let _ = fs::remove_file(DOWNLOAD_MANIFEST_PATH);
+        let _ = fs::remove_dir_all(WORK_STAGING_FOLDER);
+
+        let _ = send_download_phase(DownloadPhase::Cancelled);
+    }
+}
+
+/// How a download which was extracted as it arrived ended
+enum StreamedDownload {
+    /// The navigation data was extracted into the staging folder
+    Extracted,
+    /// The zip file can't be extracted as it arrives, so it has to be extracted from the temp file instead
+    Unsupported,
+    /// The downloaded data couldn't be extracted or verified
+    Corrupt(anyhow::Error),
+}
+
+impl DownloadNavigationData {
+    /// Download, verify and install the navigation data
+    async fn download_and_install(&self) -> Result<()> {
+        send_download_phase(DownloadPhase::Downloading)?;
+        let mut manifest = self.probe_download().await?;
+
+        if let Some(previous) = DownloadManifest::load() {
+            if previous.is_resumable_as(&manifest) {
+                manifest.completed_chunks = previous.completed_chunks;
+            }
         }
 
-        self.extract_navigation_data().await?;
+        // A partial download in the temp file is continued from its last complete chunk, otherwise the zip file is
+        // extracted as it arrives and a temp file which can't be resumed isn't needed anymore
+        let streamed = if manifest.completed_chunks == 0 {
+            let _ = fs::remove_file(DOWNLOAD_TEMP_FILE_PATH);
+            let _ = fs::remove_file(DOWNLOAD_MANIFEST_PATH);
 
-        // Open the connection
-        DATABASE_STATE
-            .try_lock()
-            .map_err(|_| anyhow!("can't lock DATABASE_STATE"))?
-            .open_connection()?;
+            self.download_streaming(&manifest).await?
+        } else {
+            StreamedDownload::Unsupported
+        };
 
-        // Remove the temp file
-        fs::remove_file(DOWNLOAD_TEMP_FILE_PATH)?;
+        // A download which can't be extracted or verified is likely corrupt, so don't resume it the next time
+        let staged = match streamed {
+            StreamedDownload::Extracted => match send_download_phase(DownloadPhase::Verifying) {
+                Ok(()) => verify_staged_navigation_data().await,
+                Err(err) => Err(err),
+            },
+            StreamedDownload::Corrupt(err) => Err(err),
+            // The chunks which were already streamed aren't kept, so the temp file is downloaded from the start
+            StreamedDownload::Unsupported => {
+                self.download_to_temp(manifest).await?;
+                self.stage_navigation_data().await
+            }
+        };
+
+        if let Err(err) = staged {
+            let _ = fs::remove_file(DOWNLOAD_TEMP_FILE_PATH);
+            let _ = fs::remove_file(DOWNLOAD_MANIFEST_PATH);
+            let _ = fs::remove_dir_all(WORK_STAGING_FOLDER);
+
+            return Err(err);
+        }
+
+        activate_staged_navigation_data()?;
+
+        // Remove the temp file and its manifest
+        let _ = fs::remove_file(DOWNLOAD_TEMP_FILE_PATH);
+        let _ = fs::remove_file(DOWNLOAD_MANIFEST_PATH);
 
         Ok(())
     }
-}
 
-impl DownloadNavigationData {
-    /// Download the navigation data zip file to the temp file location
-    async fn download_to_temp(&self) -> Result<()> {
+    /// Find out the size of the navigation data zip file and its validators, without downloading it
+    async fn probe_download(&self) -> Result<DownloadManifest> {
         // Figure out total size of download (this request is acting like a HEAD since we don't have those in this environment. Nothing actually gets downloaded since we are constraining the range)
         let request = NetworkRequestBuilder::new(&self.url)
             .context("can't create new NetworkRequestBuilder")?
-            .with_header(&format!("Range: bytes=0-0"))
+            .with_header("Range: bytes=0-0")
             .context(".with_header() returned None")?
             .get()
             .context(".get() returned None")?;
@@ -118,81 +208,653 @@ impl DownloadNavigationData {
             .ok_or(anyhow!("invalid content-range"))?
             .parse::<usize>()?;
 
+        // The validators let us know if the file has changed on the server since a previous partial download
+        let etag = request
+            .header_section("etag")
+            .map(|etag| etag.trim().to_owned());
+        let last_modified = request
+            .header_section("last-modified")
+            .map(|last_modified| last_modified.trim().to_owned());
+
+        Ok(DownloadManifest {
+            url: DownloadManifest::strip_query(&self.url).to_owned(),
+            total_bytes,
+            chunk_size: DOWNLOAD_CHUNK_SIZE_BYTES,
+            completed_chunks: 0,
+            etag,
+            last_modified,
+        })
+    }
+
+    /// Download the navigation data zip file and extract it into the staging folder as it arrives. The downloaded
+    /// chunks are only held in memory until they are extracted, so the zip file is never stored as a whole. This means
+    /// an interrupted download starts over from the beginning.
+    ///
+    /// The staging folder is removed unless the navigation data was extracted. An error means the download itself
+    /// failed.
+    ///
+    /// * `manifest` - The manifest describing the download
+    async fn download_streaming(&self, manifest: &DownloadManifest) -> Result<StreamedDownload> {
+        let extractor = StreamingExtractor::new()?;
+
+        // The extractor is dropped by the time streaming ends, so none of its files are open when staging is removed
+        let streamed = self.stream_chunks(manifest, extractor).await;
+
+        if !matches!(streamed, Ok(StreamedDownload::Extracted)) {
+            let _ = fs::remove_dir_all(WORK_STAGING_FOLDER);
+        }
+
+        streamed
+    }
+
+    /// Download the chunks of the navigation data zip file and push them into the extractor
+    ///
+    /// * `manifest` - The manifest describing the download
+    /// * `extractor` - The extractor writing into the staging folder
+    async fn stream_chunks(
+        &self,
+        manifest: &DownloadManifest,
+        mut extractor: StreamingExtractor,
+    ) -> Result<StreamedDownload> {
+        let total_chunks = manifest.total_bytes.div_ceil(DOWNLOAD_CHUNK_SIZE_BYTES);
+
+        let mut hasher = crc32fast::Hasher::new();
+        let started_at = Instant::now();
+
+        for i in 0..total_chunks {
+            let data = self
+                .download_chunk(i, manifest.total_bytes, 0, started_at)
+                .await?;
+
+            hasher.update(&data);
+
+            match extractor.push(&data) {
+                // The chunks after the central directory are still needed to verify the checksum
+                Ok(StreamStatus::Extracting | StreamStatus::Finished) => {}
+                Ok(StreamStatus::Unsupported) => return Ok(StreamedDownload::Unsupported),
+                Err(err) => return Ok(StreamedDownload::Corrupt(err)),
+            }
+        }
+
+        let verified = extractor
+            .finish()
+            .and_then(|()| match &self.checksum {
+                Some(checksum) => self.compare_checksum(checksum, hasher.finalize()),
+                None => Ok(()),
+            })
+            .and_then(|()| ensure_staged_cycle_info());
+
+        Ok(match verified {
+            Ok(()) => StreamedDownload::Extracted,
+            Err(err) => StreamedDownload::Corrupt(err),
+        })
+    }
+
+    /// Download a chunk of the navigation data zip file, reporting the download progress before it is requested
+    ///
+    /// * `chunk` - The chunk number (starting at 0) to download
+    /// * `total_bytes` - The size of the zip file
+    /// * `first_chunk` - The chunk which the download was started or resumed from
+    /// * `started_at` - When the download was started or resumed
+    async fn download_chunk(
+        &self,
+        chunk: usize,
+        total_bytes: usize,
+        first_chunk: usize,
+        started_at: Instant,
+    ) -> Result<Vec<u8>> {
+        // Calculate the range for the chunk
+        let range_start = chunk * DOWNLOAD_CHUNK_SIZE_BYTES;
+        let range_end = ((chunk + 1) * DOWNLOAD_CHUNK_SIZE_BYTES - 1).min(total_bytes - 1);
+
+        // Report the current download progress
+        InterfaceEvent::send_download_progress_event(download_progress(
+            chunk,
+            total_bytes,
+            first_chunk,
+            started_at.elapsed().as_secs_f64(),
+        ))?;
+
+        // Dispatch the request
+        NetworkRequestBuilder::new(&self.url)
+            .context("can't create new NetworkRequestBuilder")?
+            .with_header(&format!("Range: bytes={range_start}-{range_end}"))
+            .context(".with_header() returned None")?
+            .get()
+            .context(".get() returned None")?
+            .wait_for_data()
+            .await
+    }
+
+    /// Download the navigation data zip file to the temp file location, continuing from the last complete chunk in the
+    /// manifest
+    ///
+    /// * `manifest` - The manifest describing the download
+    async fn download_to_temp(&self, mut manifest: DownloadManifest) -> Result<()> {
         // Total amount of chunks to download.  We need to download the data in chunks of DOWNLOAD_CHUNK_SIZE_BYTES to avoid a timeout, so we need to keep track of a "working" accumulation of all responses
-        let total_chunks = total_bytes.div_ceil(DOWNLOAD_CHUNK_SIZE_BYTES);
+        let total_chunks = manifest.total_bytes.div_ceil(DOWNLOAD_CHUNK_SIZE_BYTES);
 
         // Store the download to a file to avoid holding in-memory
         let mut download_file = OpenOptions::new()
             .write(true)
             .create(true)
-            .truncate(true)
+            .truncate(manifest.completed_chunks == 0)
             .open(DOWNLOAD_TEMP_FILE_PATH)?;
 
-        for i in 0..total_chunks {
-            // Calculate the range for the current chunk
-            let range_start = i * DOWNLOAD_CHUNK_SIZE_BYTES;
-            let range_end = ((i + 1) * DOWNLOAD_CHUNK_SIZE_BYTES - 1).min(total_bytes - 1);
+        // Drop anything past the last complete chunk, as it may have only been partially written
+        let resume_offset = (manifest.completed_chunks * DOWNLOAD_CHUNK_SIZE_BYTES) as u64;
+        download_file.set_len(resume_offset)?;
+        download_file.seek(SeekFrom::Start(resume_offset))?;
 
-            // Report the current download progress
-            InterfaceEvent::send_download_progress_event(DownloadProgressEvent {
-                total_bytes,
-                downloaded_bytes: range_start,
-                current_chunk: i,
-                total_chunks,
-            })?;
+        manifest.save()?;
+
+        let first_chunk = manifest.completed_chunks;
+        let started_at = Instant::now();
 
-            // Dispatch the request
-            let data = NetworkRequestBuilder::new(&self.url)
-                .context("can't create new NetworkRequestBuilder")?
-                .with_header(&format!("Range: bytes={range_start}-{range_end}"))
-                .context(".with_header() returned None")?
-                .get()
-                .context(".get() returned None")?
-                .wait_for_data()
+        for i in first_chunk..total_chunks {
+            let data = self
+                .download_chunk(i, manifest.total_bytes, first_chunk, started_at)
                 .await?;
 
             // Write to limit how much data we hold in memory at a time (will be a max of DOWNLOAD_CHUNK_SIZE_BYTES)
             download_file.write_all(&data)?;
+            download_file.flush()?;
+
+            // Only mark the chunk as complete once it has been written, so an interruption never skips data
+            manifest.completed_chunks = i + 1;
+            manifest.save()?;
         }
 
         Ok(())
     }
 
-    /// Extract the navigation data files from the zip file located in the temp location
-    async fn extract_navigation_data(&self) -> Result<()> {
-        // Load the zip archive
-        let mut zip = ZipArchive::new(BufReader::new(File::open(DOWNLOAD_TEMP_FILE_PATH)?))?;
+    /// Verify the downloaded file, then extract it to the staging folder and verify the navigation data in it
+    async fn stage_navigation_data(&self) -> Result<()> {
+        if let Some(checksum) = &self.checksum {
+            self.verify_checksum(checksum)?;
+        }
 
-        // Ensure parent folder exists (ignore the result as it will return an error if it already exists)
-        let _ = fs::create_dir_all(WORK_NAVIGATION_DATA_FOLDER);
+        send_download_phase(DownloadPhase::Extracting)?;
+        extract_navigation_data(DOWNLOAD_TEMP_FILE_PATH).await?;
 
-        // Write the cycle.json file
-        let mut cycle_file = OpenOptions::new()
-            .write(true)
-            .create(true)
-            .truncate(true)
-            .open(WORK_CYCLE_JSON_PATH)?;
+        send_download_phase(DownloadPhase::Verifying)?;
+        verify_staged_navigation_data().await
+    }
 
-        std::io::copy(&mut zip.by_name("cycle.json")?, &mut cycle_file)?;
+    /// Verify the CRC32 checksum of the downloaded file in the temp location
+    fn verify_checksum(&self, expected: &str) -> Result<()> {
+        let mut file = BufReader::new(File::open(DOWNLOAD_TEMP_FILE_PATH)?);
+        let mut hasher = crc32fast::Hasher::new();
 
-        // Write the db file
-        let db_name = zip
-            .file_names()
-            .find(|f| f.to_lowercase().ends_with(".s3db"))
-            .ok_or(anyhow!(
-                "unable to find sqlite db in zip from url {}",
+        loop {
+            let buffer = file.fill_buf()?;
+            if buffer.is_empty() {
+                break;
+            }
+
+            hasher.update(buffer);
+
+            let length = buffer.len();
+            file.consume(length);
+        }
+
+        self.compare_checksum(expected, hasher.finalize())
+    }
+
+    /// Compare the CRC32 checksum of the downloaded data with the expected checksum
+    ///
+    /// * `expected` - The expected checksum, as a hexadecimal string
+    /// * `actual` - The checksum of the downloaded data
+    fn compare_checksum(&self, expected: &str, actual: u32) -> Result<()> {
+        let actual = format!("{actual:08x}");
+        if !actual.eq_ignore_ascii_case(expected.trim()) {
+            return Err(anyhow!(
+                "checksum mismatch for download from url {}: expected {expected}, got {actual}",
                 self.url
-            ))?
-            .to_owned();
+            ));
+        }
 
-        let mut db_file = OpenOptions::new()
-            .write(true)
-            .create(true)
-            .truncate(true)
-            .open(WORK_DB_PATH)?;
+        Ok(())
+    }
+}
 
-        std::io::copy(&mut zip.by_name(&db_name)?, &mut db_file)?;
+/// Calculate the download progress before a chunk is requested. The transfer rate only considers what was downloaded
+/// since the download was started or resumed
+///
+/// * `chunk` - The chunk number (starting at 0) which is about to be downloaded
+/// * `total_bytes` - The size of the zip file
+/// * `first_chunk` - The chunk which the download was started or resumed from
+/// * `elapsed_seconds` - The time since the download was started or resumed
+fn download_progress(
+    chunk: usize,
+    total_bytes: usize,
+    first_chunk: usize,
+    elapsed_seconds: f64,
+) -> DownloadProgressEvent {
+    let downloaded_bytes = chunk * DOWNLOAD_CHUNK_SIZE_BYTES;
+    let transferred_bytes = downloaded_bytes - first_chunk * DOWNLOAD_CHUNK_SIZE_BYTES;
+    let bytes_per_second = (transferred_bytes > 0 && elapsed_seconds > 0.0)
+        .then(|| transferred_bytes as f64 / elapsed_seconds);
+
+    DownloadProgressEvent {
+        total_bytes,
+        downloaded_bytes,
+        current_chunk: chunk,
+        total_chunks: total_bytes.div_ceil(DOWNLOAD_CHUNK_SIZE_BYTES),
+        bytes_per_second,
+        eta_seconds: bytes_per_second.map(|rate| (total_bytes - downloaded_bytes) as f64 / rate),
+    }
+}
+
+/// Report that a download or install has entered a new phase
+fn send_download_phase(phase: DownloadPhase) -> Result<()> {
+    InterfaceEvent::send_download_phase_event(DownloadPhaseEvent { phase, error: None })
+}
+
+/// Report that a download or install has finished, either with `Done` or `Failed`
+///
+/// * `result` - The result of the download or install
+fn send_final_download_phase(result: &Result<()>) -> Result<()> {
+    InterfaceEvent::send_download_phase_event(match result {
+        Ok(()) => DownloadPhaseEvent {
+            phase: DownloadPhase::Done,
+            error: None,
+        },
+        Err(err) => DownloadPhaseEvent {
+            phase: DownloadPhase::Failed,
+            error: Some(err.to_string()),
+        },
+    })
+}
+
+/// Install the verified navigation data in the staging folder, making it the active navigation data
+fn activate_staged_navigation_data() -> Result<()> {
+    send_download_phase(DownloadPhase::Activating)?;
+
+    // Only swap the connection if DATABASE_STATE has already been initialized - otherwise we end up unnecessarily copying the bundled data and instantly replacing it (due to initialization logic in database state)
+    if Lazy::get(&DATABASE_STATE).is_some() {
+        // The current database is only dropped now that the new one has been verified, and is restored if the new one can't be opened
+        DATABASE_STATE
+            .try_lock()
+            .map_err(|_| anyhow!("can't lock DATABASE_STATE"))?
+            .install_staged_navigation_data()
+    } else {
+        install_staged_navigation_data_files()
+    }
+}
+
+/// Extract the navigation data files from a package into the staging folder. The format of the package is detected
+/// from its magic bytes, and can either be a zip file, a zstd compressed database or a zstd compressed tar file.
+///
+/// If the package only contains the database, the cycle info JSON is created from its header.
+///
+/// * `package_path` - The path to the package
+async fn extract_navigation_data(package_path: &str) -> Result<()> {
+    let mut package_file = File::open(package_path)?;
+    let total_bytes = package_file.metadata()?.len();
+
+    let mut magic = [0; 4];
+    package_file.read_exact(&mut magic)?;
+    package_file.rewind()?;
+
+    // Start from an empty staging folder, in case a previous install was interrupted
+    let _ = fs::remove_dir_all(WORK_STAGING_FOLDER);
+    fs::create_dir_all(WORK_STAGING_FOLDER)?;
+
+    match magic {
+        ZIP_MAGIC => extract_zip(package_file, total_bytes, package_path)?,
+        ZSTD_MAGIC => extract_zstd(package_file, total_bytes, package_path)?,
+        _ => return Err(anyhow!("unknown package format of {package_path}")),
+    }
+
+    ensure_staged_cycle_info()
+}
+
+/// Extract the navigation data files from a zip file into the staging folder
+///
+/// The zip archive checks the CRC32 of each file as it is extracted, so a corrupt archive fails here
+///
+/// * `zip_file` - The zip file
+/// * `total_bytes` - The size of the zip file
+/// * `zip_path` - The path to the zip file
+fn extract_zip(zip_file: File, total_bytes: u64, zip_path: &str) -> Result<()> {
+    let mut zip = ZipArchive::new(BufReader::new(zip_file))?;
+
+    // Write the cycle.json file, if there is one
+    match zip.by_name("cycle.json") {
+        Ok(mut cycle_entry) => {
+            let mut cycle_file = File::create(WORK_STAGING_CYCLE_JSON_PATH)?;
+            extract_file(&mut cycle_entry, &mut cycle_file, total_bytes)?;
+        }
+        Err(ZipError::FileNotFound) => {}
+        Err(err) => return Err(err.into()),
+    }
+
+    // Write the db file
+    let db_name = zip
+        .file_names()
+        .find(|f| f.to_lowercase().ends_with(".s3db"))
+        .ok_or(anyhow!("unable to find sqlite db in zip {zip_path}"))?
+        .to_owned();
+
+    let mut db_file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(WORK_STAGING_DB_PATH)?;
+
+    extract_file(&mut zip.by_name(&db_name)?, &mut db_file, total_bytes)?;
+
+    Ok(())
+}
+
+/// Extract the navigation data files from a zstd compressed package into the staging folder. The package either
+/// contains the database itself, or a tar file with the database and optionally the cycle info JSON.
+///
+/// * `package_file` - The package file
+/// * `total_bytes` - The size of the package file
+/// * `package_path` - The path to the package file
+fn extract_zstd(package_file: File, total_bytes: u64, package_path: &str) -> Result<()> {
+    let mut decoder = zstd::Decoder::new(ProgressReader::new(package_file, total_bytes))?;
+
+    // The start of the decompressed data tells a database apart from a tar file. Both are at least one block long
+    let mut header = [0; TAR_BLOCK_SIZE];
+    decoder
+        .read_exact(&mut header)
+        .map_err(|err| match err.kind() {
+            std::io::ErrorKind::UnexpectedEof => {
+                anyhow!("package {package_path} is too short to be a database or tar file")
+            }
+            _ => err.into(),
+        })?;
+    let mut data = Cursor::new(header).chain(decoder);
+
+    if header.starts_with(SQLITE_MAGIC) {
+        let mut db_file = File::create(WORK_STAGING_DB_PATH)?;
+        std::io::copy(&mut data, &mut db_file)?;
+
+        return Ok(());
+    }
+
+    if !header[TAR_MAGIC_OFFSET..].starts_with(TAR_MAGIC) {
+        return Err(anyhow!("unknown package format inside {package_path}"));
+    }
+
+    let mut found_db = false;
+    for entry in tar::Archive::new(data).entries()? {
+        let mut entry = entry?;
+        let name = entry.path()?.to_string_lossy().into_owned();
+        let name = name.trim_start_matches("./");
+
+        let destination = if name == "cycle.json" {
+            WORK_STAGING_CYCLE_JSON_PATH
+        } else if name.to_lowercase().ends_with(".s3db") && !found_db {
+            found_db = true;
+            WORK_STAGING_DB_PATH
+        } else {
+            continue;
+        };
+
+        std::io::copy(&mut entry, &mut File::create(destination)?)?;
+    }
 
+    if !found_db {
+        return Err(anyhow!("unable to find sqlite db in tar {package_path}"));
+    }
+
+    Ok(())
+}
+
+/// Reports the extraction progress of a package as the position within the package file while it is read
+struct ProgressReader<R> {
+    inner: R,
+    /// The size of the package file
+    total_bytes: u64,
+    read_bytes: u64,
+    last_reported: u64,
+}
+
+impl<R> ProgressReader<R> {
+    fn new(inner: R, total_bytes: u64) -> Self {
+        Self {
+            inner,
+            total_bytes,
+            read_bytes: 0,
+            last_reported: 0,
+        }
+    }
+}
+
+impl<R: Read> Read for ProgressReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let length = self.inner.read(buf)?;
+        self.read_bytes += length as u64;
+
+        if length == 0 || self.read_bytes - self.last_reported >= EXTRACTION_PROGRESS_INTERVAL_BYTES
+        {
+            InterfaceEvent::send_extraction_progress_event(ExtractionProgressEvent {
+                total_bytes: self.total_bytes,
+                extracted_bytes: self.read_bytes,
+            })
+            .map_err(std::io::Error::other)?;
+
+            self.last_reported = self.read_bytes;
+        }
+
+        Ok(length)
+    }
+}
+
+/// Copy a file out of the zip archive, reporting the extraction progress as the position within the zip file
+///
+/// * `file` - The file in the zip archive
+/// * `destination` - The file to write the extracted data to
+/// * `total_bytes` - The size of the zip file
+fn extract_file(file: &mut ZipFile, destination: &mut File, total_bytes: u64) -> Result<()> {
+    let data_start = file.data_start();
+    let compressed_size = file.compressed_size();
+    let size = file.size().max(1);
+
+    let mut buffer = vec![0; DOWNLOAD_CHUNK_SIZE_BYTES];
+    let mut extracted = 0;
+    let mut last_reported = 0;
+
+    loop {
+        let length = file.read(&mut buffer)?;
+
+        // The compressed data is consumed at roughly the same rate as the data is extracted
+        if length == 0 || extracted - last_reported >= EXTRACTION_PROGRESS_INTERVAL_BYTES {
+            InterfaceEvent::send_extraction_progress_event(ExtractionProgressEvent {
+                total_bytes,
+                extracted_bytes: data_start + compressed_size * extracted / size,
+            })?;
+
+            last_reported = extracted;
+        }
+
+        if length == 0 {
+            break;
+        }
+
+        destination.write_all(&buffer[..length])?;
+        extracted += length as u64;
+    }
+
+    Ok(())
+}
+
+#[derive(Deserialize)]
+pub struct InstallNavigationDataFromFile {
+    /// The path to either a package (`.zip`, `.s3db.zst` or `.tar.zst`), or a folder containing a `cycle.json` and
+    /// `.s3db` file. It must be within the package folder (starting with `.\`) or the work folder (starting with
+    /// `\work`)
+    path: String,
+}
+
+impl Function for InstallNavigationDataFromFile {
+    type ReturnType = ();
+
+    async fn run(&mut self) -> Result<Self::ReturnType> {
+        let result = self.install().await;
+
+        send_final_download_phase(&result)?;
+
+        result
+    }
+
+    fn on_cancel() {
+        let _ = fs::remove_dir_all(WORK_STAGING_FOLDER);
+
+        let _ = send_download_phase(DownloadPhase::Cancelled);
+    }
+}
+
+impl InstallNavigationDataFromFile {
+    /// Stage, verify and install the navigation data
+    async fn install(&self) -> Result<()> {
+        self.validate_path()?;
+
+        if let Err(err) = self.stage_navigation_data().await {
+            let _ = fs::remove_dir_all(WORK_STAGING_FOLDER);
+
+            return Err(err);
+        }
+
+        activate_staged_navigation_data()
+    }
+
+    /// Ensure that the path is within the package or work folder, and doesn't leave it through a `..` component
+    fn validate_path(&self) -> Result<()> {
+        let in_allowed_folder = INSTALL_FROM_FILE_PATH_PREFIXES
+            .iter()
+            .any(|prefix| self.path.starts_with(prefix));
+        let leaves_folder = self
+            .path
+            .split(['/', '\\'])
+            .any(|component| component == "..");
+
+        if !in_allowed_folder || leaves_folder {
+            return Err(anyhow!(
+                "path {} must be within the package or work folder",
+                self.path
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Copy or extract the navigation data into the staging folder, mark it as sideloaded and verify it
+    async fn stage_navigation_data(&self) -> Result<()> {
+        send_download_phase(DownloadPhase::Extracting)?;
+
+        if fs::metadata(&self.path)?.is_dir() {
+            self.copy_navigation_data()?;
+        } else {
+            extract_navigation_data(&self.path).await?;
+        }
+
+        let mut cycle_info = CycleInfo::from_path(Path::new(WORK_STAGING_CYCLE_JSON_PATH))?;
+        cycle_info.source = NavigationDataSource::Sideloaded;
+        cycle_info.write_to_path(Path::new(WORK_STAGING_CYCLE_JSON_PATH))?;
+
+        send_download_phase(DownloadPhase::Verifying)?;
+        verify_staged_navigation_data().await
+    }
+
+    /// Copy a loose `cycle.json` and `.s3db` pair from the folder at `path` into the staging folder
+    fn copy_navigation_data(&self) -> Result<()> {
+        let file_names = fs::read_dir(&self.path)?
+            .filter_map(Result::ok)
+            .filter_map(|entry| entry.file_name().to_str().map(|name| name.to_owned()))
+            .collect::<Vec<_>>();
+
+        let db_name = file_names
+            .iter()
+            .find(|name| name.to_lowercase().ends_with(".s3db"))
+            .ok_or(anyhow!("unable to find sqlite db in folder {}", self.path))?;
+
+        // Start from an empty staging folder, in case a previous install was interrupted
+        let _ = fs::remove_dir_all(WORK_STAGING_FOLDER);
+        fs::create_dir_all(WORK_STAGING_FOLDER)?;
+
+        fs::copy(
+            format!("{}/cycle.json", self.path),
+            WORK_STAGING_CYCLE_JSON_PATH,
+        )?;
+        fs::copy(format!("{}/{db_name}", self.path), WORK_STAGING_DB_PATH)?;
+
+        Ok(())
+    }
+}
+
+/// The progress of a download into the temp file, persisted so that an interrupted download can be resumed
+#[derive(Serialize, Deserialize)]
+struct DownloadManifest {
+    /// The URL of the download, without its query string (which may contain short lived signatures)
+    url: String,
+    total_bytes: usize,
+    chunk_size: usize,
+    /// The number of chunks, from the start of the file, which have been completely written to the temp file
+    completed_chunks: usize,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+impl DownloadManifest {
+    /// Strips the query string from a URL
+    fn strip_query(url: &str) -> &str {
+        url.split('?').next().unwrap_or(url)
+    }
+
+    /// Load the manifest of a previous download, if there is one
+    fn load() -> Option<Self> {
+        let file = File::open(DOWNLOAD_MANIFEST_PATH).ok()?;
+
+        serde_json::from_reader(BufReader::new(file)).ok()
+    }
+
+    /// Save the manifest next to the temp file
+    fn save(&self) -> Result<()> {
+        fs::write(DOWNLOAD_MANIFEST_PATH, serde_json::to_vec(self)?)?;
+
+        Ok(())
+    }
+
+    /// Whether the download tracked by this manifest can be continued by the download described by `other`.
+    ///
+    /// Requires the same file and chunking, and at least one validator which is unchanged.
+    fn is_resumable_as(&self, other: &DownloadManifest) -> bool {
+        let validator_matches = match (&self.etag, &other.etag) {
+            (Some(a), Some(b)) => a == b,
+            _ => self.last_modified.is_some() && self.last_modified == other.last_modified,
+        };
+
+        let temp_file_complete = fs::metadata(DOWNLOAD_TEMP_FILE_PATH).is_ok_and(|metadata| {
+            metadata.len() >= (self.completed_chunks * self.chunk_size) as u64
+        });
+
+        self.url == other.url
+            && self.total_bytes == other.total_bytes
+            && self.chunk_size == other.chunk_size
+            && validator_matches
+            && temp_file_complete
+    }
+}
+
+/// Cancels a queued or running function by its call id.
+///
+/// The cancellation itself happens as soon as the call arrives (see `InterfaceFunction::cancel_target`), so running
+/// this only reports that it has been processed. If there is no function with the id, the call is rejected instead of
+/// being run.
+#[derive(Deserialize)]
+pub struct CancelFunction {
+    id: String,
+}
+
+impl Function for CancelFunction {
+    type ReturnType = ();
+
+    async fn run(&mut self) -> Result<Self::ReturnType> {
         Ok(())
     }
 }
@@ -331,6 +993,22 @@ make_function!(
     GetDatabaseInfo {} => DatabaseInfo : get_database_info()
 );
 
+make_function!(
+    ListInstalledNavigationData {} => Vec<InstalledNavigationData> : list_installed_navigation_data()
+);
+
+make_function!(
+    ActivateNavigationData {
+        id: String
+    } => () : activate_navigation_data(id)
+);
+
+make_function!(
+    DeleteNavigationData {
+        id: String
+    } => () : delete_navigation_data(id)
+);
+
 make_function!(
     ExecuteSQLQuery {
         sql: String,
@@ -344,6 +1022,26 @@ make_function!(
     } => Airport : get_airport(ident)
 );
 
+make_function!(
+    FindAirports {
+        search: AirportSearch
+    } => Vec<Airport> : find_airports(search)
+);
+
+make_function!(
+    GetAirportByIata {
+        iata: String
+    } => Airport : get_airport_by_iata(iata)
+);
+
+make_function!(
+    GetAirportDetails {
+        ident: String,
+        include: Vec<AirportDetailsInclude>,
+        aircraft_category: Option<AircraftCategory>
+    } => AirportDetails : get_airport_details(ident, include, aircraft_category)
+);
+
 make_function!(
     GetWaypoints {
         ident: String
@@ -385,8 +1083,9 @@ make_function!(
 make_function!(
     GetWaypointsInRange {
         center: Coordinates,
-        range: f64
-    } => Vec<Waypoint> : get_waypoints_in_range(center, range)
+        range: f64,
+        type_filter: Option<WaypointTypeFilter>
+    } => Vec<Waypoint> : get_waypoints_in_range(center, range, type_filter)
 );
 
 make_function!(
@@ -403,6 +1102,29 @@ make_function!(
     } => Vec<NdbNavaid> : get_ndb_navaids_in_range(center, range)
 );
 
+make_function!(
+    GetNavaidsByFrequency {
+        frequency: f64,
+        near: Coordinates,
+        max_results: Option<usize>
+    } => Vec<NavaidWithDistance> : get_navaids_by_frequency(frequency, near, max_results)
+);
+
+make_function!(
+    GetReceivableNavaids {
+        position: Coordinates,
+        altitude: f64
+    } => Vec<NavaidWithDistance> : get_receivable_navaids(position, altitude)
+);
+
+make_function!(
+    GetNavUpdateCandidates {
+        position: Coordinates,
+        altitude: f64,
+        count: usize
+    } => NavUpdateCandidates : get_nav_update_candidates(position, altitude, count)
+);
+
 make_function!(
     GetAirwaysInRange {
         center: Coordinates,
@@ -431,6 +1153,27 @@ make_function!(
     } => Vec<Communication> : get_communications_in_range(center, range)
 );
 
+make_function!(
+    GetCommunicationsForFir {
+        fir_ident: String
+    } => Vec<Communication> : get_communications_for_fir(fir_ident)
+);
+
+make_function!(
+    GetCommunicationsByFrequency {
+        frequency: f64,
+        near: Coordinates,
+        range: Option<f64>,
+        max_results: Option<usize>
+    } => Vec<Communication> : get_communications_by_frequency(frequency, near, range, max_results)
+);
+
+make_function!(
+    GetCenterFrequencyAt {
+        position: Coordinates
+    } => Option<Communication> : get_center_frequency_at(position)
+);
+
 make_function!(
     GetRunwaysAtAirport {
         airport_ident: String
@@ -438,21 +1181,116 @@ make_function!(
 );
 
 make_function!(
-    GetDeparturesAtAirport {
+    GetRunwayPairsAtAirport {
         airport_ident: String
-    } => Vec<Departure> : get_departures_at_airport(airport_ident)
+    } => Vec<Runway> : get_runway_pairs_at_airport(airport_ident)
+);
+
+make_function!(
+    GetRunwayComponents {
+        airport_ident: String,
+        wind: Wind,
+        min_length: Option<f64>,
+        surfaces: Option<Vec<RunwaySurface>>
+    } => Vec<RunwayWindComponents> : get_runway_components(airport_ident, wind, min_length, surfaces)
+);
+
+make_function!(
+    GetRunwayAtPosition {
+        position: Coordinates,
+        true_heading: f64
+    } => Option<RunwayThreshold> : get_runway_at_position(position, true_heading)
+);
+
+make_function!(
+    GetGateAtPosition {
+        position: Coordinates,
+        tolerance: f64
+    } => Option<Gate> : get_gate_at_position(position, tolerance)
+);
+
+make_function!(
+    GetDeparturesAtAirport {
+        airport_ident: String,
+        aircraft_category: Option<AircraftCategory>
+    } => Vec<Departure> : get_departures_at_airport(airport_ident, aircraft_category)
 );
 
 make_function!(
     GetArrivalsAtAirport {
-        airport_ident: String
-    } => Vec<Arrival> : get_arrivals_at_airport(airport_ident)
+        airport_ident: String,
+        aircraft_category: Option<AircraftCategory>
+    } => Vec<Arrival> : get_arrivals_at_airport(airport_ident, aircraft_category)
 );
 
 make_function!(
     GetApproachesAtAirport {
-        airport_ident: String
-    } => Vec<Approach> : get_approaches_at_airport(airport_ident)
+        airport_ident: String,
+        aircraft_category: Option<AircraftCategory>
+    } => Vec<Approach> : get_approaches_at_airport(airport_ident, aircraft_category)
+);
+
+make_function!(
+    GetDepartureSummariesAtAirport {
+        airport_ident: String,
+        aircraft_category: Option<AircraftCategory>
+    } => Vec<ProcedureSummary> : get_departure_summaries_at_airport(airport_ident, aircraft_category)
+);
+
+make_function!(
+    GetDeparture {
+        airport_ident: String,
+        ident: String,
+        aircraft_category: Option<AircraftCategory>
+    } => Departure : get_departure(airport_ident, ident, aircraft_category)
+);
+
+make_function!(
+    GetArrivalSummariesAtAirport {
+        airport_ident: String,
+        aircraft_category: Option<AircraftCategory>
+    } => Vec<ProcedureSummary> : get_arrival_summaries_at_airport(airport_ident, aircraft_category)
+);
+
+make_function!(
+    GetArrival {
+        airport_ident: String,
+        ident: String,
+        aircraft_category: Option<AircraftCategory>
+    } => Arrival : get_arrival(airport_ident, ident, aircraft_category)
+);
+
+make_function!(
+    GetApproachSummariesAtAirport {
+        airport_ident: String,
+        aircraft_category: Option<AircraftCategory>
+    } => Vec<ProcedureSummary> : get_approach_summaries_at_airport(airport_ident, aircraft_category)
+);
+
+make_function!(
+    GetApproach {
+        airport_ident: String,
+        ident: String,
+        aircraft_category: Option<AircraftCategory>
+    } => Approach : get_approach(airport_ident, ident, aircraft_category)
+);
+
+make_function!(
+    GetProcedureVerticalProfile {
+        airport_ident: String,
+        kind: ProcedureKind,
+        ident: String,
+        runway_transition: Option<String>,
+        enroute_transition: Option<String>,
+        aircraft_category: Option<AircraftCategory>
+    } => VerticalProfile : get_procedure_vertical_profile(
+        airport_ident,
+        kind,
+        ident,
+        runway_transition,
+        enroute_transition,
+        aircraft_category
+    )
 );
 
 make_function!(
@@ -475,8 +1313,11 @@ make_function!(
 
 make_function!(
     GetCommunicationsAtAirport {
-        airport_ident: String
-    } => Vec<Communication> : get_communications_at_airport(airport_ident)
+        airport_ident: String,
+        active_at: Option<u64>,
+        position: Option<Coordinates>,
+        altitude: Option<f64>
+    } => Vec<Communication> : get_communications_at_airport(airport_ident, active_at, position, altitude)
 );
 
 make_function!(
@@ -569,6 +1410,7 @@ macro_rules! define_interface_functions {
             enum FunctionStatus {
                 Success,
                 Error,
+                Cancelled,
             }
 
             /// The structure of a function result to be passed on the commbus
@@ -585,6 +1427,8 @@ macro_rules! define_interface_functions {
                     id: String,
                     args: serde_json::Value,
                     future: futures_lite::future::BoxedLocal<anyhow::Result<serde_json::Value>>,
+                    /// Whether the future has been polled, so the function may have done something to clean up
+                    started: bool,
                 }
 
                 impl [<$fn_name Wrapper>] {
@@ -596,13 +1440,14 @@ macro_rules! define_interface_functions {
                             Ok(serde_json::to_value(result)?)
                          });
 
-                        Ok(Self { id, args, future })
+                        Ok(Self { id, args, future, started: false })
                     }
 
                     fn run(&mut self) -> anyhow::Result<RunStatus> {
                         // We allow the function run to be async in order to wait for certain conditions.
                         // However, MSFS WASM modules are not multithreaded so we need to yield back to the main thread.
                         // We get around this by polling once per update, and then continuing to poll (if needed) in later updates.
+                        self.started = true;
                         match futures_lite::future::block_on(futures_lite::future::poll_once(&mut self.future)) {
                             Some(result) => {
                                 match result {
@@ -639,6 +1484,45 @@ macro_rules! define_interface_functions {
                             None => Ok(RunStatus::InProgress),
                         }
                     }
+
+                    fn cancel(self) -> anyhow::Result<()> {
+                        // Drop the future first so that nothing it holds is in use during cleanup
+                        drop(self.future);
+
+                        // A function which never started hasn't written anything, and whatever is there may belong to
+                        // the function running ahead of it
+                        if self.started {
+                            $fn_name::on_cancel();
+                        }
+
+                        // Send the cancelled result across the commbus
+                        let serialized = serde_json::to_string(&FunctionResult {
+                            id: self.id,
+                            status: FunctionStatus::Cancelled,
+                            data: Some(serde_json::to_value("Function was cancelled")?),
+                        })?;
+                        msfs::commbus::CommBus::call(
+                            "NAVIGRAPH_FunctionResult",
+                            &serialized,
+                            msfs::commbus::CommBusBroadcastFlags::All,
+                        );
+                        Ok(())
+                    }
+
+                    fn reject(self, err: anyhow::Error) -> anyhow::Result<()> {
+                        // Send the error result across the commbus without running the function
+                        let serialized = serde_json::to_string(&FunctionResult {
+                            id: self.id,
+                            status: FunctionStatus::Error,
+                            data: Some(serde_json::to_value(&err.to_string())?),
+                        })?;
+                        msfs::commbus::CommBus::call(
+                            "NAVIGRAPH_FunctionResult",
+                            &serialized,
+                            msfs::commbus::CommBusBroadcastFlags::All,
+                        );
+                        Ok(())
+                    }
                 }
             )*
 
@@ -682,6 +1566,27 @@ macro_rules! define_interface_functions {
                     }
                 }
 
+                /// Get the id of the call of this function
+                pub fn id(&self) -> &str {
+                    match self {
+                        $( Self::$fn_name(wrapper) => &wrapper.id, )*
+                    }
+                }
+
+                /// Cancel the function, dropping its execution and sending a cancelled result
+                pub fn cancel(self) -> anyhow::Result<()> {
+                    match self {
+                        $( Self::$fn_name(wrapper) => wrapper.cancel(), )*
+                    }
+                }
+
+                /// Reject the function without running it, sending an error result
+                pub fn reject(self, err: anyhow::Error) -> anyhow::Result<()> {
+                    match self {
+                        $( Self::$fn_name(wrapper) => wrapper.reject(err), )*
+                    }
+                }
+
                 // Get a `BTreeMap` representation of the current function's call data (looks identical to what was called via the commbus)
                 pub fn get_function_details(&self) -> std::collections::BTreeMap<String, serde_json::Value> {
                     match self {
@@ -701,10 +1606,18 @@ macro_rules! define_interface_functions {
 
 define_interface_functions!(
     DownloadNavigationData,
+    CancelFunction,
     GetNavigationDataInstallStatus,
+    InstallNavigationDataFromFile,
+    ListInstalledNavigationData,
+    ActivateNavigationData,
+    DeleteNavigationData,
     GetDatabaseInfo,
     ExecuteSQLQuery,
     GetAirport,
+    FindAirports,
+    GetAirportByIata,
+    GetAirportDetails,
     GetWaypoints,
     GetVhfNavaids,
     GetNdbNavaids,
@@ -714,14 +1627,31 @@ define_interface_functions!(
     GetWaypointsInRange,
     GetVhfNavaidsInRange,
     GetNdbNavaidsInRange,
+    GetNavaidsByFrequency,
+    GetReceivableNavaids,
+    GetNavUpdateCandidates,
     GetAirwaysInRange,
     GetControlledAirspacesInRange,
     GetRestrictiveAirspacesInRange,
     GetCommunicationsInRange,
+    GetCommunicationsForFir,
+    GetCommunicationsByFrequency,
+    GetCenterFrequencyAt,
     GetRunwaysAtAirport,
+    GetRunwayPairsAtAirport,
+    GetRunwayComponents,
+    GetRunwayAtPosition,
+    GetGateAtPosition,
     GetDeparturesAtAirport,
     GetArrivalsAtAirport,
     GetApproachesAtAirport,
+    GetDepartureSummariesAtAirport,
+    GetDeparture,
+    GetArrivalSummariesAtAirport,
+    GetArrival,
+    GetApproachSummariesAtAirport,
+    GetApproach,
+    GetProcedureVerticalProfile,
     GetWaypointsAtAirport,
     GetNdbNavaidsAtAirport,
     GetGatesAtAirport,
@@ -729,3 +1659,96 @@ define_interface_functions!(
     GetGlsNavaidsAtAirport,
     GetPathPointsAtAirport
 );
+
+/// The lanes which interface functions are scheduled in. Functions in different lanes run alongside each other, while
+/// the call order is preserved within each lane
+#[derive(Clone, Copy, PartialEq)]
+pub enum FunctionLane {
+    /// Functions which read from the current database, and usually finish immediately
+    Query,
+    /// Long running functions which change the installed navigation data
+    Maintenance,
+}
+
+impl InterfaceFunction {
+    /// Get the lane which this function is scheduled in
+    pub fn lane(&self) -> FunctionLane {
+        match self {
+            Self::DownloadNavigationData(_)
+            | Self::InstallNavigationDataFromFile(_)
+            | Self::ActivateNavigationData(_)
+            | Self::DeleteNavigationData(_) => FunctionLane::Maintenance,
+            _ => FunctionLane::Query,
+        }
+    }
+
+    /// Get the id of the call which this function cancels, if it is a `CancelFunction`
+    pub fn cancel_target(&self) -> Option<String> {
+        match self {
+            Self::CancelFunction(wrapper) => CancelFunction::new(wrapper.args.clone())
+                .ok()
+                .map(|function| function.id),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn download_progress_without_transfer_rate() {
+        let total_bytes = 3 * DOWNLOAD_CHUNK_SIZE_BYTES + 100;
+        let progress = download_progress(0, total_bytes, 0, 0.0);
+
+        assert_eq!(progress.downloaded_bytes, 0);
+        assert_eq!(progress.current_chunk, 0);
+        assert_eq!(progress.total_chunks, 4);
+        assert!(progress.bytes_per_second.is_none());
+        assert!(progress.eta_seconds.is_none());
+
+        // Nothing has been transferred yet since the download was resumed
+        let resumed = download_progress(2, total_bytes, 2, 10.0);
+        assert_eq!(resumed.downloaded_bytes, 2 * DOWNLOAD_CHUNK_SIZE_BYTES);
+        assert!(resumed.bytes_per_second.is_none());
+    }
+
+    #[test]
+    fn download_progress_since_start_or_resume() {
+        let total_bytes = 4 * DOWNLOAD_CHUNK_SIZE_BYTES;
+
+        let progress = download_progress(2, total_bytes, 0, 4.0);
+        let rate = (2 * DOWNLOAD_CHUNK_SIZE_BYTES) as f64 / 4.0;
+        assert_eq!(progress.bytes_per_second, Some(rate));
+        assert_eq!(progress.eta_seconds, Some(4.0));
+
+        let resumed = download_progress(3, total_bytes, 2, 2.0);
+        assert_eq!(resumed.downloaded_bytes, 3 * DOWNLOAD_CHUNK_SIZE_BYTES);
+        assert_eq!(
+            resumed.bytes_per_second,
+            Some(DOWNLOAD_CHUNK_SIZE_BYTES as f64 / 2.0)
+        );
+        assert_eq!(resumed.eta_seconds, Some(2.0));
+    }
+
+    #[test]
+    fn install_paths_must_stay_within_the_package_or_work_folder() {
+        let validate = |path: &str| {
+            InstallNavigationDataFromFile {
+                path: path.to_string(),
+            }
+            .validate_path()
+        };
+
+        assert!(validate(".\\navdata\\cycle.zip").is_ok());
+        assert!(validate("\\work/navdata.tar.zst").is_ok());
+        assert!(validate("\\work\\sideload").is_ok());
+
+        assert!(validate("C:\\navdata.zip").is_err());
+        assert!(validate("/tmp/navdata.zip").is_err());
+        assert!(validate("navdata.zip").is_err());
+        assert!(validate("\\work/../navdata.zip").is_err());
+        assert!(validate(".\\..\\other\\navdata.zip").is_err());
+    }
+}
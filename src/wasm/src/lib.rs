@@ -1,7 +1,8 @@
 use std::{cell::RefCell, collections::VecDeque, rc::Rc, time::Instant};
 
 use anyhow::{anyhow, Result};
-use funcs::{InterfaceFunction, RunStatus};
+use database::CycleInfo;
+use funcs::{FunctionLane, InterfaceFunction, RunStatus};
 use msfs::commbus::{CommBus, CommBusBroadcastFlags};
 use sentry::{integrations::anyhow::capture_anyhow, protocol::Context};
 use sentry_gauge::{wrap_gauge_with_sentry, SentryGauge};
@@ -17,11 +18,14 @@ mod funcs;
 mod futures;
 /// The sentry wrapper implementation around the MSFS gauge callbacks
 mod sentry_gauge;
+/// Extraction of navigation data zip files while they are being downloaded
+mod streaming;
 
 /// Amount of MS between dispatches of the heartbeat commbus event
 const HEARTBEAT_INTERVAL_MS: u128 = 1000;
 
 /// The data associated with the `DownloadProgress` event
+#[serde_with::skip_serializing_none]
 #[derive(Serialize)]
 pub struct DownloadProgressEvent {
     /// The total amount of bytes to download
@@ -32,6 +36,50 @@ pub struct DownloadProgressEvent {
     pub current_chunk: usize,
     /// The total number of chunks needed to download
     pub total_chunks: usize,
+    /// The average transfer rate since the download was started or resumed, once a chunk has been downloaded
+    pub bytes_per_second: Option<f64>,
+    /// The estimated amount of seconds until the download finishes, based on `bytes_per_second`
+    pub eta_seconds: Option<f64>,
+}
+
+/// The phases which a navigation data download goes through, in order
+#[derive(Serialize, Clone, Copy)]
+pub enum DownloadPhase {
+    Downloading,
+    Extracting,
+    Verifying,
+    Activating,
+    Done,
+    Failed,
+    Cancelled,
+}
+
+/// The data associated with the `DownloadPhaseChanged` event
+#[serde_with::skip_serializing_none]
+#[derive(Serialize)]
+pub struct DownloadPhaseEvent {
+    /// The phase which the download has entered
+    pub phase: DownloadPhase,
+    /// The reason the download failed, only present in the `Failed` phase
+    pub error: Option<String>,
+}
+
+/// The data associated with the `ExtractionProgress` event
+#[derive(Serialize)]
+pub struct ExtractionProgressEvent {
+    /// The size in bytes of the zip file being extracted
+    pub total_bytes: u64,
+    /// The amount of bytes of the zip file which have been extracted
+    pub extracted_bytes: u64,
+}
+
+/// The data associated with the `NavigationDataChanged` event
+#[derive(Serialize)]
+pub struct NavigationDataChangedEvent {
+    /// The navigation data which was installed before the change, if there was any
+    pub old: Option<CycleInfo>,
+    /// The navigation data which is now installed, if it can be read
+    pub new: Option<CycleInfo>,
 }
 
 /// The types of events that can be emitted from the interface
@@ -39,6 +87,9 @@ pub struct DownloadProgressEvent {
 enum NavigraphEventType {
     Heartbeat,
     DownloadProgress,
+    DownloadPhaseChanged,
+    ExtractionProgress,
+    NavigationDataChanged,
 }
 
 /// The structure of an event message
@@ -49,41 +100,129 @@ struct InterfaceEvent {
 }
 
 impl InterfaceEvent {
-    /// Send a heartbeat event across the commbus
-    pub fn send_heartbeat() -> Result<()> {
-        let event = Self {
-            event: NavigraphEventType::Heartbeat,
-            data: None,
-        };
-
-        let serialized = serde_json::to_string(&event)?;
+    /// Send an event across the commbus
+    fn send(self) -> Result<()> {
+        let serialized = serde_json::to_string(&self)?;
 
         CommBus::call("NAVIGRAPH_Event", &serialized, CommBusBroadcastFlags::All);
 
         Ok(())
     }
 
+    /// Send a heartbeat event across the commbus
+    pub fn send_heartbeat() -> Result<()> {
+        Self {
+            event: NavigraphEventType::Heartbeat,
+            data: None,
+        }
+        .send()
+    }
+
     /// Send a download progress event across the commbus
     ///
     /// * `event` - The download progress event data
     pub fn send_download_progress_event(event: DownloadProgressEvent) -> Result<()> {
-        let event = Self {
+        Self {
             event: NavigraphEventType::DownloadProgress,
             data: Some(serde_json::to_value(event)?),
-        };
+        }
+        .send()
+    }
 
-        let serialized = serde_json::to_string(&event)?;
+    /// Send a download phase change event across the commbus
+    ///
+    /// * `event` - The download phase event data
+    pub fn send_download_phase_event(event: DownloadPhaseEvent) -> Result<()> {
+        Self {
+            event: NavigraphEventType::DownloadPhaseChanged,
+            data: Some(serde_json::to_value(event)?),
+        }
+        .send()
+    }
 
-        CommBus::call("NAVIGRAPH_Event", &serialized, CommBusBroadcastFlags::All);
+    /// Send an extraction progress event across the commbus
+    ///
+    /// * `event` - The extraction progress event data
+    pub fn send_extraction_progress_event(event: ExtractionProgressEvent) -> Result<()> {
+        Self {
+            event: NavigraphEventType::ExtractionProgress,
+            data: Some(serde_json::to_value(event)?),
+        }
+        .send()
+    }
 
-        Ok(())
+    /// Send a navigation data changed event across the commbus
+    ///
+    /// * `event` - The navigation data changed event data
+    pub fn send_navigation_data_changed_event(event: NavigationDataChangedEvent) -> Result<()> {
+        Self {
+            event: NavigraphEventType::NavigationDataChanged,
+            data: Some(serde_json::to_value(event)?),
+        }
+        .send()
+    }
+}
+
+/// The queues of functions waiting to be run, with one queue per lane
+#[derive(Default)]
+struct ProcessingQueues {
+    query: VecDeque<InterfaceFunction>,
+    maintenance: VecDeque<InterfaceFunction>,
+}
+
+impl ProcessingQueues {
+    /// Get the queue for a lane
+    fn lane_mut(&mut self, lane: FunctionLane) -> &mut VecDeque<InterfaceFunction> {
+        match lane {
+            FunctionLane::Query => &mut self.query,
+            FunctionLane::Maintenance => &mut self.maintenance,
+        }
+    }
+
+    /// Remove a function from whichever queue it is in by its call id
+    fn remove_by_id(&mut self, id: &str) -> Option<InterfaceFunction> {
+        [FunctionLane::Query, FunctionLane::Maintenance]
+            .into_iter()
+            .find_map(|lane| {
+                let queue = self.lane_mut(lane);
+                let index = queue.iter().position(|function| function.id() == id)?;
+
+                queue.remove(index)
+            })
+    }
+
+    /// Run the functions in a queue. Only one function runs at a time, so if it returns InProgress, don't continue on to the next item in order to preserve call order
+    fn process(queue: &mut VecDeque<InterfaceFunction>) {
+        while let Some(function) = queue.front_mut() {
+            match function.run() {
+                Ok(RunStatus::InProgress) => break,
+                Ok(RunStatus::Finished) => {
+                    queue.pop_front();
+                }
+                Err(e) => {
+                    // Report error
+                    sentry::with_scope(
+                        |scope| {
+                            scope.set_context(
+                                "Interface Function",
+                                Context::Other(function.get_function_details()),
+                            );
+                        },
+                        || capture_anyhow(&e),
+                    );
+                    println!("[NAVIGRAPH]: Error occurred in function execution: {e}");
+                    // Remove item
+                    queue.pop_front();
+                }
+            };
+        }
     }
 }
 
 /// The main state for the interface
 struct NavigationDataInterface<'a> {
     _commbus: CommBus<'a>,
-    processing_queue: Rc<RefCell<VecDeque<InterfaceFunction>>>,
+    processing_queues: Rc<RefCell<ProcessingQueues>>,
     last_heartbeat: Instant,
 }
 
@@ -94,14 +233,14 @@ impl SentryGauge for NavigationDataInterface<'_> {
     {
         // Initialize commbus
         let mut commbus = CommBus::default();
-        let processing_queue = Rc::new(RefCell::new(VecDeque::new()));
+        let processing_queues = Rc::new(RefCell::new(ProcessingQueues::default()));
 
         // Create the NAVIGRAPH_CallFunction callback
-        let processing_queue_clone = Rc::clone(&processing_queue);
+        let processing_queues_clone = Rc::clone(&processing_queues);
         commbus
             .register("NAVIGRAPH_CallFunction", move |args| {
-                // Try to get the queue
-                let Ok(mut processing_queue) = processing_queue_clone.try_borrow_mut() else {
+                // Try to get the queues
+                let Ok(mut processing_queues) = processing_queues_clone.try_borrow_mut() else {
                     sentry::capture_message(
                         "Unable to borrow processing queue",
                         sentry::Level::Warning,
@@ -110,7 +249,7 @@ impl SentryGauge for NavigationDataInterface<'_> {
                 };
 
                 // Parse the message as a function. We need to trim off the null terminator at the end
-                let params = match serde_json::from_str::<InterfaceFunction>(
+                let mut params = match serde_json::from_str::<InterfaceFunction>(
                     args.trim_end_matches(char::from(0)),
                 ) {
                     Ok(p) => p,
@@ -125,8 +264,29 @@ impl SentryGauge for NavigationDataInterface<'_> {
                     }
                 };
 
-                // Finally, push the function into our queue
-                processing_queue.push_back(params);
+                // Cancellations are handled as soon as they arrive, as the function they cancel may be blocking its lane
+                if let Some(target_id) = params.cancel_target() {
+                    // The cancel call is answered right away instead of being queued, so its result isn't held up by a
+                    // long running function and the order of the queued calls is kept
+                    let result = match processing_queues.remove_by_id(&target_id) {
+                        Some(function) => {
+                            if let Err(e) = function.cancel() {
+                                capture_anyhow(&e);
+                            }
+
+                            params.run().map(|_| ())
+                        }
+                        None => params.reject(anyhow!("no function with id {target_id}")),
+                    };
+
+                    if let Err(e) = result {
+                        capture_anyhow(&e);
+                    }
+                    return;
+                }
+
+                // Finally, push the function into the queue of its lane
+                processing_queues.lane_mut(params.lane()).push_back(params);
             })
             .ok_or(anyhow!("Unable to register NAVIGRAPH_CallFunction"))?;
 
@@ -136,38 +296,17 @@ impl SentryGauge for NavigationDataInterface<'_> {
 
         Ok(Self {
             _commbus: commbus,
-            processing_queue,
+            processing_queues,
             last_heartbeat,
         })
     }
 
     fn update(&mut self) -> Result<()> {
-        let mut queue = self.processing_queue.try_borrow_mut()?;
+        let mut queues = self.processing_queues.try_borrow_mut()?;
 
-        // Process one function at a time. If the function returns InProgress, don't continue on to the next item in order to preserve call order
-        while let Some(function) = queue.front_mut() {
-            match function.run() {
-                Ok(RunStatus::InProgress) => break,
-                Ok(RunStatus::Finished) => {
-                    queue.pop_front();
-                }
-                Err(e) => {
-                    // Report error
-                    sentry::with_scope(
-                        |scope| {
-                            scope.set_context(
-                                "Interface Function",
-                                Context::Other(function.get_function_details()),
-                            );
-                        },
-                        || capture_anyhow(&e),
-                    );
-                    println!("[NAVIGRAPH]: Error occurred in function execution: {e}");
-                    // Remove item
-                    queue.pop_front();
-                }
-            };
-        }
+        // Maintenance jobs run alongside queries, which keep being served from the current database until it is swapped
+        ProcessingQueues::process(&mut queues.maintenance);
+        ProcessingQueues::process(&mut queues.query);
 
         // Send heartbeat if we have passed the interval
         if self.last_heartbeat.elapsed().as_millis() >= HEARTBEAT_INTERVAL_MS {
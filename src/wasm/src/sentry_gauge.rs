@@ -84,15 +84,13 @@ impl SentryPersistentState {
     /// Load the state
     pub fn load() -> Self {
         // Read from the local file. If that fails, it is likely that the file doesn't exist, so we can just create an empty state
-        let status = std::fs::read_to_string(SENTRY_FILE)
+        std::fs::read_to_string(SENTRY_FILE)
             .ok()
             .and_then(|contents| serde_json::from_str(&contents).ok())
             .unwrap_or(SentryPersistentState {
                 user_id: Uuid::new_v4(),
                 reports: vec![],
-            });
-
-        status
+            })
     }
 
     /// Flush all pending requests to the file system
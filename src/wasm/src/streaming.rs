@@ -0,0 +1,330 @@
+use std::{
+    fs::{self, File},
+    io::Write,
+};
+
+use anyhow::{anyhow, Result};
+use flate2::{Decompress, FlushDecompress, Status};
+use zstd::stream::raw::{InBuffer, Operation, OutBuffer};
+
+use crate::database::{WORK_STAGING_CYCLE_JSON_PATH, WORK_STAGING_DB_PATH, WORK_STAGING_FOLDER};
+
+/// The signature at the start of each local file header
+const LOCAL_FILE_HEADER_SIGNATURE: u32 = 0x04034b50;
+/// The signature at the start of each central directory header, which follows the last local file
+const CENTRAL_DIRECTORY_HEADER_SIGNATURE: u32 = 0x02014b50;
+/// The signature of the end of central directory record, which follows the last local file if there are no files
+const END_OF_CENTRAL_DIRECTORY_SIGNATURE: u32 = 0x06054b50;
+/// The length in bytes of a local file header, excluding the file name and extra field
+const LOCAL_FILE_HEADER_LENGTH: usize = 30;
+
+/// The general purpose flag which is set when a file is encrypted
+const FLAG_ENCRYPTED: u16 = 1;
+/// The general purpose flag which is set when the sizes and CRC32 of a file are only known after its data
+const FLAG_DATA_DESCRIPTOR: u16 = 1 << 3;
+
+/// The compression method of files which are stored without compression
+const METHOD_STORED: u16 = 0;
+/// The compression method of deflated files
+const METHOD_DEFLATED: u16 = 8;
+/// The compression method of zstd compressed files
+const METHOD_ZSTD: u16 = 93;
+
+/// The size of the buffer which compressed data is decompressed into (set to 64KB currently)
+const OUTPUT_BUFFER_SIZE_BYTES: usize = 64 * 1024;
+
+/// The state of a streaming extraction after a chunk has been pushed
+#[derive(PartialEq)]
+pub enum StreamStatus {
+    /// More data is needed to finish extracting
+    Extracting,
+    /// The central directory has been reached, so every file has been extracted
+    Finished,
+    /// The zip file can't be extracted as it arrives, so it has to be downloaded in full first
+    Unsupported,
+}
+
+/// How the data of a file is decoded
+enum Decoder {
+    Stored,
+    Deflated(Box<Decompress>),
+    Zstd(Box<zstd::stream::raw::Decoder<'static>>),
+}
+
+/// A file in the zip file which is being extracted
+struct Entry {
+    name: String,
+    /// The file to extract to, or `None` if the file isn't needed and is skipped
+    destination: Option<File>,
+    decoder: Decoder,
+    /// The amount of compressed bytes of the file which have not arrived yet
+    remaining_bytes: u64,
+    /// The CRC32 of the uncompressed file, as stated in its local file header
+    crc32: u32,
+    hasher: crc32fast::Hasher,
+}
+
+/// Extracts the navigation data files from a zip file into the staging folder while it is being downloaded, using
+/// the local file headers in front of each file instead of the central directory at the end of the zip file.
+///
+/// This only works when the sizes of the files are known from their local file headers, otherwise the zip file has to
+/// be extracted once it has been downloaded in full.
+pub struct StreamingExtractor {
+    /// The data which has arrived, but has not been processed yet
+    buffer: Vec<u8>,
+    output: Vec<u8>,
+    entry: Option<Entry>,
+    /// Whether a local file header has been read, which shows that the data is a zip file
+    started: bool,
+    finished: bool,
+    extracted_cycle_info: bool,
+    extracted_database: bool,
+}
+
+impl StreamingExtractor {
+    /// Create an extractor, starting from an empty staging folder
+    pub fn new() -> Result<Self> {
+        // Start from an empty staging folder, in case a previous install was interrupted
+        let _ = fs::remove_dir_all(WORK_STAGING_FOLDER);
+        fs::create_dir_all(WORK_STAGING_FOLDER)?;
+
+        Ok(Self {
+            buffer: Vec::new(),
+            output: vec![0; OUTPUT_BUFFER_SIZE_BYTES],
+            entry: None,
+            started: false,
+            finished: false,
+            extracted_cycle_info: false,
+            extracted_database: false,
+        })
+    }
+
+    /// Extract as much as possible from the next chunk of the zip file
+    ///
+    /// * `data` - The next chunk of the zip file
+    pub fn push(&mut self, data: &[u8]) -> Result<StreamStatus> {
+        if self.finished {
+            return Ok(StreamStatus::Finished);
+        }
+
+        self.buffer.extend_from_slice(data);
+
+        loop {
+            if let Some(entry) = &mut self.entry {
+                let length = (self.buffer.len() as u64).min(entry.remaining_bytes) as usize;
+                if length == 0 {
+                    return Ok(StreamStatus::Extracting);
+                }
+
+                Self::decode(entry, &self.buffer[..length], &mut self.output)?;
+                self.buffer.drain(..length);
+                entry.remaining_bytes -= length as u64;
+
+                if entry.remaining_bytes == 0 {
+                    self.finish_entry()?;
+                }
+
+                continue;
+            }
+
+            if self.buffer.len() < 4 {
+                return Ok(StreamStatus::Extracting);
+            }
+
+            match read_u32(&self.buffer, 0) {
+                LOCAL_FILE_HEADER_SIGNATURE => {}
+                CENTRAL_DIRECTORY_HEADER_SIGNATURE | END_OF_CENTRAL_DIRECTORY_SIGNATURE => {
+                    self.finished = true;
+                    self.buffer.clear();
+
+                    return Ok(StreamStatus::Finished);
+                }
+                // Packages which aren't zip files are extracted from the temp file
+                _ if !self.started => return Ok(StreamStatus::Unsupported),
+                _ => return Err(anyhow!("invalid local file header in zip")),
+            }
+
+            if self.buffer.len() < LOCAL_FILE_HEADER_LENGTH {
+                return Ok(StreamStatus::Extracting);
+            }
+
+            let flags = read_u16(&self.buffer, 6);
+            let method = read_u16(&self.buffer, 8);
+            let crc32 = read_u32(&self.buffer, 14);
+            let compressed_size = read_u32(&self.buffer, 18);
+            let name_length = read_u16(&self.buffer, 26) as usize;
+            let extra_length = read_u16(&self.buffer, 28) as usize;
+
+            let header_length = LOCAL_FILE_HEADER_LENGTH + name_length + extra_length;
+            if self.buffer.len() < header_length {
+                return Ok(StreamStatus::Extracting);
+            }
+
+            // The size of the file is only known from the central directory in these cases (a size of u32::MAX means
+            // the real size is in the zip64 extra field)
+            if flags & (FLAG_ENCRYPTED | FLAG_DATA_DESCRIPTOR) != 0 || compressed_size == u32::MAX {
+                return Ok(StreamStatus::Unsupported);
+            }
+
+            let name = String::from_utf8_lossy(
+                &self.buffer[LOCAL_FILE_HEADER_LENGTH..LOCAL_FILE_HEADER_LENGTH + name_length],
+            )
+            .into_owned();
+
+            let destination_path = if name == "cycle.json" && !self.extracted_cycle_info {
+                Some(WORK_STAGING_CYCLE_JSON_PATH)
+            } else if name.to_lowercase().ends_with(".s3db") && !self.extracted_database {
+                Some(WORK_STAGING_DB_PATH)
+            } else {
+                None
+            };
+
+            // Files which aren't needed are skipped, so only the compression of the needed files matters
+            let decoder = match (destination_path, method) {
+                (None, _) | (Some(_), METHOD_STORED) => Decoder::Stored,
+                (Some(_), METHOD_DEFLATED) => Decoder::Deflated(Box::new(Decompress::new(false))),
+                (Some(_), METHOD_ZSTD) => {
+                    Decoder::Zstd(Box::new(zstd::stream::raw::Decoder::new()?))
+                }
+                _ => return Ok(StreamStatus::Unsupported),
+            };
+
+            let destination = destination_path.map(File::create).transpose()?;
+
+            self.started = true;
+            self.buffer.drain(..header_length);
+            self.entry = Some(Entry {
+                name,
+                destination,
+                decoder,
+                remaining_bytes: compressed_size as u64,
+                crc32,
+                hasher: crc32fast::Hasher::new(),
+            });
+
+            // Empty files have no data to wait for
+            if compressed_size == 0 {
+                self.finish_entry()?;
+            }
+        }
+    }
+
+    /// Ensure that the database has been extracted once the whole zip file has been pushed. The cycle info JSON is
+    /// optional, as it can be created from the database.
+    pub fn finish(self) -> Result<()> {
+        if self.entry.is_some() {
+            return Err(anyhow!("zip ended in the middle of a file"));
+        }
+
+        if !self.extracted_database {
+            return Err(anyhow!("unable to find sqlite db in zip"));
+        }
+
+        Ok(())
+    }
+
+    /// Decode the next part of the compressed data of a file and write it to its destination
+    ///
+    /// * `entry` - The file being extracted
+    /// * `input` - The next part of the compressed data
+    /// * `output` - The buffer to decompress into
+    fn decode(entry: &mut Entry, input: &[u8], output: &mut [u8]) -> Result<()> {
+        let Some(destination) = &mut entry.destination else {
+            return Ok(());
+        };
+
+        match &mut entry.decoder {
+            Decoder::Stored => {
+                destination.write_all(input)?;
+                entry.hasher.update(input);
+            }
+            Decoder::Deflated(decompress) => {
+                let mut input = input;
+                loop {
+                    let total_in = decompress.total_in();
+                    let total_out = decompress.total_out();
+
+                    let status = decompress.decompress(input, output, FlushDecompress::None)?;
+
+                    let consumed = (decompress.total_in() - total_in) as usize;
+                    let produced = (decompress.total_out() - total_out) as usize;
+
+                    destination.write_all(&output[..produced])?;
+                    entry.hasher.update(&output[..produced]);
+                    input = &input[consumed..];
+
+                    // Keep going while the output buffer is filled up, as there may be more output pending
+                    if status == Status::StreamEnd
+                        || (consumed == 0 && produced == 0)
+                        || (input.is_empty() && produced < output.len())
+                    {
+                        break;
+                    }
+                }
+            }
+            Decoder::Zstd(decoder) => {
+                let mut input = InBuffer::around(input);
+                loop {
+                    let mut output_buffer = OutBuffer::around(&mut *output);
+                    decoder.run(&mut input, &mut output_buffer)?;
+                    let produced = output_buffer.pos();
+
+                    destination.write_all(&output[..produced])?;
+                    entry.hasher.update(&output[..produced]);
+
+                    // Keep going while the output buffer is filled up, as there may be more output pending
+                    if input.pos() == input.src.len() && produced < output.len() {
+                        break;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Verify the CRC32 of the file which has just been extracted, and move on to the next local file header
+    fn finish_entry(&mut self) -> Result<()> {
+        let Some(entry) = self.entry.take() else {
+            return Ok(());
+        };
+
+        let Some(mut destination) = entry.destination else {
+            return Ok(());
+        };
+
+        destination.flush()?;
+
+        let actual = entry.hasher.finalize();
+        if actual != entry.crc32 {
+            return Err(anyhow!(
+                "checksum mismatch for {} in zip: expected {:08x}, got {actual:08x}",
+                entry.name,
+                entry.crc32
+            ));
+        }
+
+        if entry.name == "cycle.json" {
+            self.extracted_cycle_info = true;
+        } else {
+            self.extracted_database = true;
+        }
+
+        Ok(())
+    }
+}
+
+/// Read a little endian u16 from a buffer
+fn read_u16(buffer: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([buffer[offset], buffer[offset + 1]])
+}
+
+/// Read a little endian u32 from a buffer
+fn read_u32(buffer: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        buffer[offset],
+        buffer[offset + 1],
+        buffer[offset + 2],
+        buffer[offset + 3],
+    ])
+}